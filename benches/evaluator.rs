@@ -0,0 +1,49 @@
+#![feature(test)]
+extern crate test;
+
+use std::str::FromStr;
+
+use headsup_analyzer::core::cards::Cards;
+use test::Bencher;
+
+const HANDS: [&str; 8] = [
+    "AhKd2c7s9dQhJc",
+    "8h7h2c7s9d3d4c",
+    "AhJh9h4h2hKc3d",
+    "KsKdKh2c2dQh7s",
+    "As2d3h4c5s5dTc",
+    "6h7h8s9hTcKh2h",
+    "9s9d9h9cAdAh7s",
+    "Tc8d6h4s2cQdJh",
+];
+
+fn hands() -> Vec<Cards> {
+    HANDS
+        .iter()
+        .map(|hand| Cards::from_str(hand).unwrap())
+        .collect()
+}
+
+#[bench]
+fn bench_lookup_table(b: &mut Bencher) {
+    let hands = hands();
+    // Build the tables before timing.
+    hands[0].analyze_madehand().unwrap();
+    b.iter(|| {
+        hands
+            .iter()
+            .map(|hand| hand.analyze_madehand().unwrap())
+            .max()
+    });
+}
+
+#[bench]
+fn bench_combinations(b: &mut Bencher) {
+    let hands = hands();
+    b.iter(|| {
+        hands
+            .iter()
+            .map(|hand| hand.enumerate_madehand().unwrap())
+            .max()
+    });
+}
@@ -1,7 +1,11 @@
 use clap::Clap;
+use std::path::PathBuf;
 
+use crate::board::board::BoardCommand;
 use crate::duel::duel::Duel;
 use crate::hand::hand::Hand;
+use crate::range::range::RangeCommand;
+use crate::showdown::showdown::ShowdownCommand;
 #[derive(Clap)]
 #[clap(
     name = "Heads-Up Analyzer",
@@ -10,16 +14,28 @@ use crate::hand::hand::Hand;
     about = "Analyze Heads-Up of poker"
 )]
 pub struct Opts {
+    /// Range chart to use instead of the one in the config dir or the built-in chart
+    #[clap(long, global = true, env = "HEADSUP_RANGES", parse(from_os_str))]
+    pub ranges: Option<PathBuf>,
     #[clap(subcommand)]
     pub sub_command: SubCommand,
 }
 
 #[derive(Clap)]
+#[allow(clippy::large_enum_variant)]
 pub enum SubCommand {
     #[clap(version = "1.0")]
     Hand(Hand),
     #[clap(version = "1.0")]
     Duel(Duel),
+    #[clap(version = "1.0", alias = "ranges")]
+    Range(RangeCommand),
+    /// Describe the texture of a flop, turn or river
+    #[clap(version = "1.0")]
+    Board(BoardCommand),
+    /// Compare two hands on a board
+    #[clap(version = "1.0")]
+    Showdown(ShowdownCommand),
 }
 
 pub fn arg_parse() -> Opts {
@@ -0,0 +1,59 @@
+use anyhow::bail;
+use clap::Clap;
+
+use crate::board::report::TextureReport;
+use crate::core::board::{Board, Street};
+use crate::duel::report::OutputFormat;
+
+#[derive(Clap)]
+pub struct BoardCommand {
+    /// Flop, turn or river such as "Kh7h2d"
+    #[clap(name = "BOARD")]
+    pub board: Board,
+    /// Output format: text or json
+    #[clap(long, default_value = "text")]
+    pub output: OutputFormat,
+}
+
+impl BoardCommand {
+    pub fn analyse(&self) -> anyhow::Result<()> {
+        if self.board.street() == Street::Preflop {
+            bail!("A board texture needs a flop, turn or river");
+        }
+        let report = TextureReport::new(&self.board);
+        match self.output {
+            OutputFormat::Text => print_texture(&report),
+            OutputFormat::Json => println!("{}", report.to_json()?),
+        }
+        Ok(())
+    }
+}
+
+pub fn print_texture(report: &TextureReport) {
+    println!(
+        "Board: {}, {}, {}, {}, {} ({})",
+        report.suitedness,
+        report.pairing,
+        report.connectedness,
+        report.high_card,
+        report.wetness,
+        report.score
+    );
+    if !report.straights.is_empty() {
+        println!("Straights: {}", report.straights.join(", "));
+    }
+    if !report.flushes.is_empty() {
+        println!("Flushes: {}", report.flushes.join(", "));
+    }
+    if let Some(last_card) = &report.last_card {
+        println!("{}: {}", last_card.card, last_card.transitions.join(", "));
+    }
+    if !report.next_cards.is_empty() {
+        let next_cards = report
+            .next_cards
+            .iter()
+            .map(|next| format!("{} {}", next.cards, next.transition))
+            .collect::<Vec<_>>();
+        println!("Next card: {}", next_cards.join(" / "));
+    }
+}
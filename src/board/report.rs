@@ -0,0 +1,114 @@
+use serde::Serialize;
+
+use crate::core::{
+    board::{Board, Street},
+    texture::{Texture, Transition},
+};
+
+#[derive(Serialize, Debug)]
+pub struct TextureReport {
+    pub suitedness: String,
+    pub pairing: String,
+    pub connectedness: String,
+    pub high_card: String,
+    pub wetness: String,
+    pub score: u8,
+    /// Highest card of every straight two hole cards can make.
+    pub straights: Vec<String>,
+    pub flushes: Vec<String>,
+    /// What the turn or river card changed, from the turn on.
+    pub last_card: Option<CardTransitionReport>,
+    /// How many of the unseen cards do what on the next street, until the river.
+    pub next_cards: Vec<NextCardsReport>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct CardTransitionReport {
+    pub card: String,
+    pub transitions: Vec<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct NextCardsReport {
+    pub transition: String,
+    pub cards: usize,
+}
+
+impl TextureReport {
+    pub fn new(board: &Board) -> Self {
+        let cards = board.cards().cards();
+        let texture = Texture::new(cards);
+        let last_card = match (board.turn(), board.river()) {
+            (Some(turn), None) => Some((&cards[..3], turn)),
+            (Some(_), Some(river)) => Some((&cards[..4], river)),
+            _ => None,
+        }
+        .map(|(previous, card)| CardTransitionReport {
+            card: card.to_string(),
+            transitions: Transition::between(previous, card)
+                .iter()
+                .map(|transition| transition.to_string())
+                .collect(),
+        });
+        let next_cards = if matches!(board.street(), Street::Flop | Street::Turn) {
+            Transition::next_cards(cards)
+                .into_iter()
+                .map(|(transition, cards)| NextCardsReport {
+                    transition: transition.to_string(),
+                    cards,
+                })
+                .collect()
+        } else {
+            vec![]
+        };
+        TextureReport {
+            suitedness: texture.suitedness.to_string(),
+            pairing: texture.pairing.to_string(),
+            connectedness: texture.connectedness.to_string(),
+            high_card: texture.high_card.to_string(),
+            wetness: texture.wetness().to_string(),
+            score: texture.score,
+            straights: texture
+                .straights
+                .iter()
+                .map(|value| format!("{}-high", value.to_char()))
+                .collect(),
+            flushes: texture
+                .flushes
+                .iter()
+                .map(|suit| suit.to_char().to_string())
+                .collect(),
+            last_card,
+            next_cards,
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn test_texture_report() {
+        let turn = TextureReport::new(&Board::from_str("Kh7h2dAh").unwrap());
+        assert_eq!(turn.suitedness, "monotone");
+        assert_eq!(turn.flushes, vec!["h"]);
+        let last_card = turn.last_card.unwrap();
+        assert_eq!(last_card.card, "Ah");
+        assert_eq!(last_card.transitions, vec!["overcard", "flush completes"]);
+        assert!(!turn.next_cards.is_empty());
+
+        let flop = TextureReport::new(&Board::from_str("9h8c7d").unwrap());
+        assert!(flop.last_card.is_none());
+        assert_eq!(flop.straights, vec!["9-high", "T-high", "J-high"]);
+
+        let river = TextureReport::new(&Board::from_str("Kh7h2dAh3c").unwrap());
+        assert!(river.next_cards.is_empty());
+    }
+}
@@ -1,15 +1,29 @@
 pub mod board;
 
+pub mod texture;
+
 pub mod player;
 
 pub mod position;
 
+pub mod profile;
+
 pub mod action;
 
+pub mod line;
+
 pub mod cards;
 
 pub mod madehand;
 
+pub mod evaluator;
+
+pub mod showdown;
+
+pub mod relative_hand;
+
+pub mod distribution;
+
 pub mod straight;
 
 pub mod range;
@@ -17,3 +31,11 @@ pub mod range;
 pub mod hand_wrapper;
 
 pub mod drawhand;
+
+pub mod equity;
+
+pub mod odds;
+
+pub mod hand_class;
+
+pub mod grid;
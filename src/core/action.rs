@@ -21,7 +21,7 @@ impl FromStr for Action {
             "THREEBETCALL" | "3BETCALL" => Ok(Self::ThreeBetCall),
             "FOURBET" | "4BET" => Ok(Self::FourBet),
             "FOURBETCALL" | "4BETCALL" => Ok(Self::FourBetCall),
-            _ => return Err(format!("Invalid action: {}", s)),
+            _ => Err(format!("Invalid action: {}", s)),
         }
     }
 }
@@ -40,23 +40,20 @@ impl Action {
     }
 
     pub fn is_after_three_bet(&self) -> bool {
-        match self {
-            Self::ThreeBetCall | Self::FourBet | Self::FourBetCall => true,
-            _ => false,
-        }
+        matches!(self, Self::ThreeBetCall | Self::FourBet | Self::FourBetCall)
     }
 }
 
 impl fmt::Display for Action {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let string = match self {
-            &Action::Call => "Call",
-            &Action::Limp => "Limp",
-            &Action::Open => "Open",
-            &Action::ThreeBet => "3bet",
-            &Action::ThreeBetCall => "3bet call",
-            &Action::FourBet => "4bet",
-            &Action::FourBetCall => "4bet call",
+        let string = match *self {
+            Action::Call => "Call",
+            Action::Limp => "Limp",
+            Action::Open => "Open",
+            Action::ThreeBet => "3bet",
+            Action::ThreeBetCall => "3bet call",
+            Action::FourBet => "4bet",
+            Action::FourBetCall => "4bet call",
         };
         write!(f, "{}", string)
     }
@@ -1,15 +1,145 @@
-use std::str::FromStr;
+use std::{fmt, str::FromStr};
+
+use rs_poker::core::Card;
 
 use crate::core::cards::Cards;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum Street {
+    Preflop,
+    Flop,
+    Turn,
+    River,
+}
+
+impl Street {
+    /// Board cards dealt by this street.
+    pub fn board_len(self) -> usize {
+        match self {
+            Street::Preflop => 0,
+            Street::Flop => 3,
+            Street::Turn => 4,
+            Street::River => 5,
+        }
+    }
+}
+
+impl fmt::Display for Street {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Street::Preflop => write!(f, "preflop"),
+            Street::Flop => write!(f, "flop"),
+            Street::Turn => write!(f, "turn"),
+            Street::River => write!(f, "river"),
+        }
+    }
+}
+
+/// Community cards: none preflop, then three, four or five.
+#[derive(Debug, Clone, Eq, Default)]
 pub struct Board {
-    pub cards: Cards,
+    cards: Cards,
+}
+
+/// The same flop in any order, and the same turn and river.
+impl PartialEq for Board {
+    fn eq(&self, other: &Self) -> bool {
+        self.cards == other.cards && self.turn() == other.turn() && self.river() == other.river()
+    }
+}
+
+impl Board {
+    pub fn new(cards: Cards) -> Result<Self, &'static str> {
+        match cards.len() {
+            0 | 3 | 4 | 5 => Ok(Board { cards }),
+            _ => Err("A board is empty (preflop) or 3 (flop), 4 (turn) or 5 (river) cards"),
+        }
+    }
+
+    pub fn cards(&self) -> &Cards {
+        &self.cards
+    }
+
+    pub fn street(&self) -> Street {
+        match self.cards.len() {
+            0 => Street::Preflop,
+            3 => Street::Flop,
+            4 => Street::Turn,
+            _ => Street::River,
+        }
+    }
+
+    pub fn flop(&self) -> Option<&[Card]> {
+        self.cards.cards().get(..3)
+    }
+
+    pub fn turn(&self) -> Option<Card> {
+        self.cards.cards().get(3).copied()
+    }
+
+    pub fn river(&self) -> Option<Card> {
+        self.cards.cards().get(4).copied()
+    }
+
+    /// Board cards still to be dealt.
+    pub fn cards_to_come(&self) -> usize {
+        Street::River.board_len() - self.cards.len()
+    }
+
+    /// This board with `cards` dealt next.
+    pub fn deal(&self, cards: &Cards) -> Result<Self, &'static str> {
+        Board::new(self.cards.union(cards)?)
+    }
 }
 
 impl FromStr for Board {
     type Err = &'static str;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Cards::from_str(s).and_then(|cards| Ok(Board { cards }))
+        Cards::from_str(s).and_then(Board::new)
+    }
+}
+
+impl fmt::Display for Board {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.cards)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_streets() {
+        let board = |s: &str| Board::from_str(s).unwrap();
+        assert_eq!(board("").street(), Street::Preflop);
+        assert_eq!(board("Kc7s2d").street(), Street::Flop);
+        assert_eq!(board("Kc7s2d9h").street(), Street::Turn);
+        assert_eq!(board("Kc7s2d9h3c").street(), Street::River);
+        for invalid in ["Kc", "Kc7s", "Kc7s2d9h3cAh", "Kc7s2d9h3cAhQd"].iter() {
+            assert!(Board::from_str(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_components() {
+        let river = Board::from_str("Kc 7s 2d 9h 3c").unwrap();
+        assert_eq!(river.flop().unwrap().len(), 3);
+        assert_eq!(river.turn().unwrap().to_string(), "9h");
+        assert_eq!(river.river().unwrap().to_string(), "3c");
+        assert_eq!(river.cards_to_come(), 0);
+
+        let flop = Board::from_str("Kc7s2d").unwrap();
+        assert_eq!(flop.turn(), None);
+        assert_eq!(flop.cards_to_come(), 2);
+        assert_eq!(Board::default().flop(), None);
+
+        let turn = flop.deal(&Cards::from_str("9h").unwrap()).unwrap();
+        assert_eq!(turn.street(), Street::Turn);
+        assert!(flop.deal(&Cards::from_str("Kc").unwrap()).is_err());
+        assert!(river.deal(&Cards::from_str("Ah").unwrap()).is_err());
+
+        assert_eq!(flop, Board::from_str("2dKc7s").unwrap());
+        assert_ne!(turn, Board::from_str("Kc7s9h2d").unwrap());
     }
 }
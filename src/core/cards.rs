@@ -1,58 +1,141 @@
 use itertools::Itertools;
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeSet, HashMap, HashSet},
+    fmt,
     str::FromStr,
 };
 
 use anyhow::Result;
 use rs_poker::core::{Card, Suit, Value};
 
-use crate::core::{drawhand::calculate_straight_draws, drawhand::DrawHand, madehand::MadeHand};
-#[derive(Debug, Clone)]
+use crate::core::{
+    drawhand::{self, calculate_straight_draws, DrawHand, Outs},
+    evaluator::{self, HandRank},
+    madehand::MadeHand,
+};
+
+/// Distinct cards in the order they were given, with a bitmask in rust_poker's card layout
+/// (4 * rank + suit, suits ordered s, h, d, c) for set operations.
+#[derive(Debug, Clone, Default)]
 pub struct Cards {
-    pub cards: Vec<Card>,
-    pub text: String,
+    cards: Vec<Card>,
+    mask: u64,
 }
 
-impl std::ops::Add for Cards {
-    type Output = Self;
-
-    fn add(self, other: Self) -> Self {
-        let mut cards = self.cards.to_owned();
-        cards.append(&mut other.cards.clone());
-        let mut text = self.text.to_owned();
-        text.push_str(&mut other.text.clone());
-        Self { cards, text }
+/// Equal when they hold the same cards, in any order.
+impl PartialEq for Cards {
+    fn eq(&self, other: &Self) -> bool {
+        self.mask == other.mask
     }
 }
 
+impl Eq for Cards {}
+
 impl FromStr for Cards {
     type Err = &'static str;
+    /// Accepts "AsKd" as well as "As Kd", "As,Kd" and "[As Kd]".
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        s.chars()
-            .into_iter()
-            .collect::<Vec<char>>()
+        let chars = s
+            .chars()
+            .filter(|c| !c.is_whitespace() && !matches!(c, ',' | '[' | ']'))
+            .collect::<Vec<char>>();
+        if chars.len() % 2 != 0 {
+            return Err("Cards must be pairs of rank and suit such as \"As\"");
+        }
+        chars
             .chunks(2)
-            .try_fold(vec![], |mut cards, chars| {
+            .try_fold(Cards::default(), |mut cards, chars| {
                 match (Value::from_char(chars[0]), Suit::from_char(chars[1])) {
                     (Some(value), Some(suit)) => {
-                        cards.push(Card { value, suit });
+                        cards.insert(Card { value, suit })?;
                         Ok(cards)
                     }
                     (_, _) => Err("Can't convert chars to Card"),
                 }
             })
-            .and_then(|cards| {
-                Ok(Cards {
-                    cards,
-                    text: s.to_string(),
-                })
-            })
+    }
+}
+
+impl fmt::Display for Cards {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.cards.iter().try_for_each(|card| write!(f, "{}", card))
     }
 }
 
 impl Cards {
+    pub fn new(cards: Vec<Card>) -> Result<Self, &'static str> {
+        cards
+            .into_iter()
+            .try_fold(Cards::default(), |mut cards, card| {
+                cards.insert(card)?;
+                Ok(cards)
+            })
+    }
+
+    pub fn cards(&self) -> &[Card] {
+        &self.cards
+    }
+
+    /// The cards as a rust_poker card mask, for `HandRange` and equity calculations.
+    pub fn mask(&self) -> u64 {
+        self.mask
+    }
+
+    pub fn len(&self) -> usize {
+        self.cards.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
+
+    pub fn contains(&self, card: &Card) -> bool {
+        self.mask & card_bit(card) != 0
+    }
+
+    pub fn insert(&mut self, card: Card) -> Result<(), &'static str> {
+        if self.contains(&card) {
+            return Err("Duplicate cards");
+        }
+        self.mask |= card_bit(&card);
+        self.cards.push(card);
+        Ok(())
+    }
+
+    /// Removes `card`, returning whether it was there.
+    pub fn remove(&mut self, card: &Card) -> bool {
+        if !self.contains(card) {
+            return false;
+        }
+        self.mask &= !card_bit(card);
+        self.cards.retain(|c| c != card);
+        true
+    }
+
+    /// These cards followed by `other`, which must not share any card with them.
+    pub fn union(&self, other: &Cards) -> Result<Cards, &'static str> {
+        if self.mask & other.mask != 0 {
+            return Err("Duplicate cards");
+        }
+        let mut cards = self.cards.clone();
+        cards.extend_from_slice(&other.cards);
+        Ok(Cards {
+            cards,
+            mask: self.mask | other.mask,
+        })
+    }
+
     pub fn analyze_madehand(&self) -> Result<MadeHand, &'static str> {
+        self.rank().map(HandRank::made_hand)
+    }
+
+    /// Comparable strength of the best 5 of these 5 to 7 cards.
+    pub fn rank(&self) -> Result<HandRank, &'static str> {
+        evaluator::evaluate(&self.cards)
+    }
+
+    /// Best made hand of every 5-card combination. The slow reference for `analyze_madehand`.
+    pub fn enumerate_madehand(&self) -> Result<MadeHand, &'static str> {
         if self.cards.len() < 5 {
             return Err("Invalid cards length");
         }
@@ -60,9 +143,9 @@ impl Cards {
         self.combination()
             .into_iter()
             .try_fold(vec![], |mut acc, cards| {
-                cards.sub_analyze().and_then(|madehand| {
+                cards.sub_analyze().map(|madehand| {
                     acc.push(madehand);
-                    Ok(acc)
+                    acc
                 })
             })
             .and_then(|madehands| match madehands.into_iter().max() {
@@ -71,67 +154,76 @@ impl Cards {
             })
     }
 
+    /// Evaluates exactly 5 cards. Straight flushes come first, then quads and full houses,
+    /// which can't be flushes or straights, then flushes before straights.
     fn sub_analyze(&self) -> Result<MadeHand, &'static str> {
-        let suited = self.suited();
+        if self.cards.len() != 5 {
+            return Err("Invalid cards length");
+        }
         let rank_as_count = self.count_rank();
-        let two_card = rank_as_count.get(&2);
-        let three_card = rank_as_count.get(&3);
-        let four_card = rank_as_count.get(&4);
-        let kickers = match rank_as_count.get(&1) {
-            Some(kickers) => kickers.clone(),
-            None => vec![],
-        };
-        let straight = self.straight();
-
-        // println!("suited: {:?}", suited);
-        // println!("two card: {:?}", two_card);
-        // println!("three card: {:?}", three_card);
-        // println!("four card: {:?}", four_card);
-        // println!("kickers: {:?}", kickers);
-        // println!("straight: {:?}", straight);
-
-        let madehand = match (four_card, three_card, two_card, straight, suited) {
-            (Some(four_card), _, _, _, _) => MadeHand::FourCard(four_card[0], kickers[0]),
-            (None, Some(three_card), Some(two_card), _, _) => {
+        let ranks = |count: u32| rank_as_count.get(&count).cloned().unwrap_or_default();
+        let (four_card, three_card, two_card, kickers) = (ranks(4), ranks(3), ranks(2), ranks(1));
+
+        let madehand = match (self.straight(), self.suited()) {
+            (Some(Value::Ace), true) => MadeHand::RoyalStraightFlush,
+            (Some(high), true) => MadeHand::StraightFlush(high),
+            _ if !four_card.is_empty() => MadeHand::FourCard(four_card[0], kickers[0]),
+            _ if !three_card.is_empty() && !two_card.is_empty() => {
                 MadeHand::FullHouse(three_card[0], two_card[0])
             }
-            (None, Some(three_card), None, _, _) => {
-                MadeHand::ThreeCard(three_card[0], kickers[0], kickers[1])
+            (None, true) => {
+                MadeHand::Flush(kickers[0], kickers[1], kickers[2], kickers[3], kickers[4])
             }
-            (None, None, Some(two_card), _, _) if two_card.len() == 2 => {
-                MadeHand::TwoPair(two_card[0], two_card[1], kickers[0])
+            (Some(high), false) => MadeHand::Straight(high),
+            _ if !three_card.is_empty() => {
+                MadeHand::ThreeCard(three_card[0], kickers[0], kickers[1])
             }
-            (None, None, Some(two_card), _, _) if two_card.len() == 1 => {
+            _ if two_card.len() == 2 => MadeHand::TwoPair(two_card[0], two_card[1], kickers[0]),
+            _ if two_card.len() == 1 => {
                 MadeHand::OnePair(two_card[0], kickers[0], kickers[1], kickers[2])
             }
-            (_, _, _, Some(MadeHand::Straight(n)), true) if n == Value::Ace => {
-                MadeHand::RoyalStraightFlush
-            }
-            (_, _, _, Some(MadeHand::Straight(n)), true) => MadeHand::StraightFlush(n),
-            (_, _, _, Some(straight), false) => straight,
-            (_, _, _, None, true) => MadeHand::Flush(kickers[0]),
             _ => MadeHand::HighCard(kickers[0], kickers[1], kickers[2], kickers[3], kickers[4]),
         };
         Ok(madehand)
     }
 
+    /// Draws in any 5 of the cards, each once, leaving out draws to a hand already made.
     pub fn analyse_drawhand(&self) -> Vec<DrawHand> {
-        let rest_card_length = 7 - self.cards.len();
+        let rest_card_length = 7_usize.saturating_sub(self.cards.len());
         if rest_card_length == 0 {
             return vec![];
         }
 
-        let init: Vec<DrawHand> = vec![];
+        let madehand = self.rank().ok().map(HandRank::made_hand);
         self.combination()
             .into_iter()
-            .map(|cards| cards.sub_analyse_drawhand(rest_card_length))
-            .fold(init, |mut acc, mut drawhands| {
-                acc.append(&mut drawhands);
-                acc
-            })
+            .flat_map(|cards| cards.sub_analyse_drawhand(rest_card_length))
+            .filter(|draw| !madehand.is_some_and(|madehand| is_made(draw, madehand)))
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    /// Draws of these hole cards on `board`, leaving out the ones the board has on its own.
+    pub fn analyse_drawhand_on(&self, board: &Cards) -> Result<Vec<DrawHand>, &'static str> {
+        let all = self.union(board)?;
+        let rest_card_length = 7_usize.saturating_sub(all.len());
+        let on_board = board.sub_analyse_drawhand(rest_card_length);
+        Ok(all
+            .analyse_drawhand()
+            .into_iter()
+            .filter(|draw| !on_board.contains(draw))
+            .collect())
+    }
+
+    /// The outs of these hole cards' draws on `board`, treating both as dead.
+    pub fn analyse_outs_on(&self, board: &Cards) -> Result<Outs, &'static str> {
+        let draws = self.analyse_drawhand_on(board)?;
+        Ok(drawhand::count_outs(&draws, self.union(board)?.cards()))
     }
 
-    fn sub_analyse_drawhand(&self, _rest_card_length: usize) -> Vec<DrawHand> {
+    /// Backdoor draws need two more cards, so none are left on the turn.
+    fn sub_analyse_drawhand(&self, rest_card_length: usize) -> Vec<DrawHand> {
         let mut result: Vec<DrawHand> = vec![];
 
         // フラッシュドロー
@@ -139,13 +231,18 @@ impl Cards {
         let backdoor_flash_draw = self.suits().into_iter().find(|(_s, count)| *count == 3);
         match (flash_draw, backdoor_flash_draw) {
             (Some(d), _) => result.push(DrawHand::FlashDraw(d.0)),
-            (None, Some(d)) => result.push(DrawHand::BackdoorFlashDraw(d.0)),
+            (None, Some(d)) if rest_card_length >= 2 => {
+                result.push(DrawHand::BackdoorFlashDraw(d.0))
+            }
             _ => {}
         };
 
         // ストレートドロー
         let values = self.values();
         let mut draw_hands = calculate_straight_draws(values);
+        if rest_card_length < 2 {
+            draw_hands.retain(|draw| !matches!(draw, DrawHand::BackDoorStraightDraw(..)));
+        }
 
         result.append(&mut draw_hands);
         result
@@ -157,8 +254,8 @@ impl Cards {
             .into_iter()
             .combinations(5)
             .map(|cards| Cards {
-                text: self.text.clone(),
-                cards: cards,
+                mask: cards.iter().fold(0, |mask, card| mask | card_bit(card)),
+                cards,
             })
             .collect()
     }
@@ -192,15 +289,16 @@ impl Cards {
         let init: HashMap<Value, u32> = HashMap::new();
         let result_init: HashMap<u32, Vec<Value>> = HashMap::new();
 
-        self.values()
-            .into_iter()
+        self.cards
+            .iter()
+            .map(|card| card.value)
             .fold(init, |mut acc, rank| {
                 *acc.entry(rank).or_insert_with(|| 0_u32) += 1;
                 acc
             })
             .into_iter()
             .fold(result_init, |mut acc, (rank, count)| {
-                acc.entry(count).or_insert_with(|| vec![]).push(rank);
+                acc.entry(count).or_default().push(rank);
                 acc
             })
             .into_iter()
@@ -211,29 +309,108 @@ impl Cards {
             .collect()
     }
 
-    fn straight(&self) -> Option<MadeHand> {
+    /// The high card of a straight, five for the wheel. Needs five different ranks.
+    fn straight(&self) -> Option<Value> {
         let values = self.values();
-        let is_straight = values.windows(2).all(|x| is_connect_values(x[0], x[1]));
-        if !is_straight {
+        if values.len() != 5 || !values.windows(2).all(|x| is_connect_values(x[0], x[1])) {
             return None;
         }
         match (values.first(), values.last()) {
-            (Some(Value::Two), Some(Value::Ace)) => Some(MadeHand::Straight(Value::Five)),
-            _ => Some(MadeHand::Straight(*values.last().unwrap())),
+            (Some(Value::Two), Some(Value::Ace)) => Some(Value::Five),
+            _ => values.last().copied(),
         }
     }
 }
 
+/// The card's bit in a rust_poker card mask.
+fn card_bit(card: &Card) -> u64 {
+    let suit = match card.suit {
+        Suit::Spade => 0,
+        Suit::Heart => 1,
+        Suit::Diamond => 2,
+        Suit::Club => 3,
+    };
+    1 << (card.value as u64 * 4 + suit)
+}
+
 fn is_connect_values(a: Value, b: Value) -> bool {
     match (a, b) {
-        (Value::Ace, Value::Five) | (Value::Five, Value::Ace) => return true,
+        (Value::Ace, Value::Five) | (Value::Five, Value::Ace) => true,
         (a, b) => (a as i32 - b as i32).abs() == 1,
     }
 }
 
+/// Whether `madehand` already is what `draw` draws to, or better than it.
+fn is_made(draw: &DrawHand, madehand: MadeHand) -> bool {
+    let flush = matches!(
+        madehand,
+        MadeHand::Flush(..)
+            | MadeHand::FullHouse(..)
+            | MadeHand::FourCard(..)
+            | MadeHand::StraightFlush(_)
+            | MadeHand::RoyalStraightFlush
+    );
+    match draw {
+        DrawHand::FlashDraw(_) | DrawHand::BackdoorFlashDraw(_) => flush,
+        DrawHand::StraightDraw(_) | DrawHand::BackDoorStraightDraw(..) => {
+            flush || matches!(madehand, MadeHand::Straight(_))
+        }
+        DrawHand::TwoOver(..) | DrawHand::OneOver(_) => false,
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use rust_poker::hand_range::get_card_mask;
+
     use super::*;
+    #[test]
+    fn test_from_str() {
+        let cards = Cards::from_str("AsKd").unwrap();
+        for text in ["As Kd", "As,Kd", "[As Kd]", "[As, Kd]"].iter() {
+            assert_eq!(Cards::from_str(text), Ok(cards.clone()));
+        }
+        assert_eq!(cards.to_string(), "AsKd");
+        assert!(Cards::from_str("AsAs").is_err());
+        assert!(Cards::from_str("AsK").is_err());
+        assert!(Cards::from_str("AsKx").is_err());
+        assert_eq!(Cards::from_str(""), Ok(Cards::default()));
+    }
+
+    #[test]
+    fn test_mask() {
+        for text in ["AsKd", "2c7h9dTsJc", "AhAdAcAs"].iter() {
+            assert_eq!(Cards::from_str(text).unwrap().mask(), get_card_mask(text));
+        }
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let mut cards = Cards::from_str("AsKd7h").unwrap();
+        let king = cards.cards()[1];
+        assert!(cards.contains(&king));
+        assert!(cards.remove(&king));
+        assert!(!cards.remove(&king));
+        assert!(!cards.contains(&king));
+        assert_eq!(cards.to_string(), "As7h");
+        assert_eq!(cards.mask(), get_card_mask("As7h"));
+        assert!(cards.insert(king).is_ok());
+        assert!(cards.insert(king).is_err());
+
+        let board = Cards::from_str("2c3c4c").unwrap();
+        assert_eq!(cards.union(&board).unwrap().to_string(), "As7hKd2c3c4c");
+        assert!(cards.union(&Cards::from_str("7h").unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_eq_ignores_order() {
+        let cards = |s: &str| Cards::from_str(s).unwrap();
+        assert_eq!(cards("AhKd"), cards("KdAh"));
+        assert_eq!(cards("2c3c4c"), cards("4c 2c 3c"));
+        assert_ne!(cards("AhKd"), cards("AhKh"));
+        assert_ne!(cards("AhKd"), cards("AhKdQs"));
+    }
+
     #[test]
     fn test_values() {
         let cards = Cards::from_str("2s3s4s2c8c").ok().unwrap();
@@ -242,4 +419,71 @@ mod tests {
             vec![Value::Two, Value::Three, Value::Four, Value::Eight]
         )
     }
+
+    #[test]
+    fn test_drawhand_by_street() {
+        let flop = Cards::from_str("4h5h6c9dKh").unwrap();
+        let draws = flop.analyse_drawhand();
+        assert!(draws.contains(&DrawHand::BackdoorFlashDraw(Suit::Heart)));
+        let turn = Cards::from_str("4h5h6c9dKh2s").unwrap();
+        let draws = turn.analyse_drawhand();
+        assert!(draws.contains(&DrawHand::StraightDraw(Value::Three)));
+        assert!(!draws.iter().any(|draw| matches!(
+            draw,
+            DrawHand::BackdoorFlashDraw(_) | DrawHand::BackDoorStraightDraw(..)
+        )));
+        let river = Cards::from_str("4h5h6c9dKh2sJc").unwrap();
+        assert!(river.analyse_drawhand().is_empty());
+    }
+
+    #[test]
+    fn test_no_draw_to_made_hand() {
+        let flush = Cards::from_str("AhKh7h2h9h3s").unwrap();
+        assert!(!flush
+            .analyse_drawhand()
+            .iter()
+            .any(|draw| matches!(draw, DrawHand::FlashDraw(_))));
+        let straight = Cards::from_str("8h9dTc7s6d2c").unwrap();
+        assert!(straight.analyse_drawhand().is_empty());
+        // A made straight can still draw to a flush.
+        let straight = Cards::from_str("8h9hTc7h6d2h").unwrap();
+        assert_eq!(
+            straight.analyse_drawhand(),
+            vec![DrawHand::FlashDraw(Suit::Heart)]
+        );
+    }
+
+    #[test]
+    fn test_drawhand_on_board() {
+        let draws = |hole: &str, board: &str| {
+            Cards::from_str(hole)
+                .unwrap()
+                .analyse_drawhand_on(&Cards::from_str(board).unwrap())
+                .unwrap()
+        };
+        // The board's three hearts are not hero's backdoor flush draw; the wheel draw is.
+        assert_eq!(
+            draws("2c3d", "AhKh7h"),
+            vec![DrawHand::BackDoorStraightDraw(Value::Four, Value::Five)]
+        );
+        assert_eq!(
+            draws("2h3d", "AhKh7h"),
+            vec![
+                DrawHand::FlashDraw(Suit::Heart),
+                DrawHand::BackDoorStraightDraw(Value::Four, Value::Five)
+            ]
+        );
+        // Four to a straight on the board is the board's draw, not hero's.
+        assert!(draws("AcAd", "5h6c7d8s").is_empty());
+        assert_eq!(
+            draws("9c2d", "5h6c7d"),
+            vec![DrawHand::StraightDraw(Value::Eight)]
+        );
+        let outs = Cards::from_str("8h9d")
+            .unwrap()
+            .analyse_outs_on(&Cards::from_str("Tc7s6d2c").unwrap())
+            .unwrap();
+        assert!(outs.draws.is_empty());
+        assert!(outs.cards.is_empty());
+    }
 }
@@ -0,0 +1,150 @@
+use std::{fmt, str::FromStr};
+
+use rust_poker::hand_range::HandRange;
+
+use crate::core::{
+    board::Board,
+    cards::Cards,
+    drawhand::DrawHand,
+    equity,
+    relative_hand::{Kicker, RelativeHand},
+};
+
+/// What one combo of a range has on the board. Made hands rank above draws, draws above air.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, PartialOrd, Ord)]
+pub enum BoardCategory {
+    Air,
+    /// No pair, but a flush draw or a straight draw.
+    Draw,
+    Made(RelativeHand),
+}
+
+impl BoardCategory {
+    pub fn of(hole: &Cards, board: &Board) -> Result<Self, &'static str> {
+        match RelativeHand::classify(hole, board)? {
+            RelativeHand::HighCard => {
+                let draw = hole
+                    .analyse_drawhand_on(board.cards())?
+                    .iter()
+                    .any(|draw| matches!(draw, DrawHand::FlashDraw(_) | DrawHand::StraightDraw(_)));
+                Ok(if draw {
+                    BoardCategory::Draw
+                } else {
+                    BoardCategory::Air
+                })
+            }
+            hand => Ok(BoardCategory::Made(hand)),
+        }
+    }
+
+    /// Top pair or better.
+    pub fn is_value(&self) -> bool {
+        matches!(self, BoardCategory::Made(hand) if *hand >= RelativeHand::TopPair(Kicker::Weak))
+    }
+
+    /// A set or better.
+    pub fn is_nut(&self) -> bool {
+        matches!(self, BoardCategory::Made(hand) if *hand >= RelativeHand::Set)
+    }
+}
+
+impl fmt::Display for BoardCategory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BoardCategory::Air => write!(f, "air"),
+            BoardCategory::Draw => write!(f, "draw"),
+            BoardCategory::Made(hand) => write!(f, "{}", hand),
+        }
+    }
+}
+
+/// Live combos of a range on a board by category, strongest first.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Distribution {
+    pub combos: usize,
+    pub categories: Vec<(BoardCategory, usize)>,
+}
+
+impl Distribution {
+    pub fn new(hand_range: &HandRange, board: &Board) -> Self {
+        let board_mask = board.cards().mask();
+        let categories = equity::split_range(hand_range, board_mask, |combo| {
+            Cards::from_str(&combo.to_string())
+                .ok()
+                .and_then(|hole| BoardCategory::of(&hole, board).ok())
+                .unwrap_or(BoardCategory::Air)
+        })
+        .into_iter()
+        .rev()
+        .map(|(category, range)| (category, range.hands.len()))
+        .collect::<Vec<_>>();
+        Distribution {
+            combos: categories.iter().map(|(_, combos)| combos).sum(),
+            categories,
+        }
+    }
+
+    pub fn share(&self, combos: usize) -> f64 {
+        if self.combos == 0 {
+            return 0.0;
+        }
+        combos as f64 / self.combos as f64
+    }
+
+    /// Share of the range with top pair or better.
+    pub fn value_share(&self) -> f64 {
+        self.share_of(BoardCategory::is_value)
+    }
+
+    /// Share of the range with a set or better.
+    pub fn nut_share(&self) -> f64 {
+        self.share_of(BoardCategory::is_nut)
+    }
+
+    fn share_of(&self, predicate: impl Fn(&BoardCategory) -> bool) -> f64 {
+        self.share(
+            self.categories
+                .iter()
+                .filter(|(category, _)| predicate(category))
+                .map(|(_, combos)| combos)
+                .sum(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_board_category() {
+        let board = Board::from_str("Kh7h2d").unwrap();
+        let category = |hole: &str| BoardCategory::of(&Cards::from_str(hole).unwrap(), &board);
+        assert_eq!(
+            category("AhKd"),
+            Ok(BoardCategory::Made(RelativeHand::TopPair(Kicker::Top)))
+        );
+        assert_eq!(category("Ah4h"), Ok(BoardCategory::Draw));
+        assert_eq!(category("QcJd"), Ok(BoardCategory::Air));
+        assert!(category("7c7d").unwrap().is_nut());
+        assert!(category("AhKd").unwrap().is_value());
+        assert!(!category("QcQd").unwrap().is_value());
+    }
+
+    #[test]
+    fn test_distribution() {
+        let board = Board::from_str("Kh7h2d").unwrap();
+        let distribution = Distribution::new(&HandRange::from_string("77,AKs".to_string()), &board);
+        // The board's 7h and Kh block three combos of 77 and one of AKs.
+        assert_eq!(distribution.combos, 6);
+        assert_eq!(
+            distribution.categories,
+            vec![
+                (BoardCategory::Made(RelativeHand::Set), 3),
+                (BoardCategory::Made(RelativeHand::TopPair(Kicker::Top)), 3),
+            ]
+        );
+        assert_eq!(distribution.nut_share(), 0.5);
+        assert_eq!(distribution.value_share(), 1.0);
+    }
+}
@@ -1,4 +1,6 @@
-use rs_poker::core::{Suit, Value};
+use std::{collections::BTreeSet, fmt};
+
+use rs_poker::core::{Card, Suit, Value};
 
 use crate::core::straight;
 
@@ -12,10 +14,100 @@ pub enum DrawHand {
     BackDoorStraightDraw(Value, Value),
 }
 
+impl fmt::Display for DrawHand {
+    /// The draw and the ranks or suit it needs, e.g. "flush draw (h)". Part of the JSON
+    /// reports, so keep it stable.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DrawHand::TwoOver(a, b) => write!(f, "two overcards ({}{})", a.to_char(), b.to_char()),
+            DrawHand::OneOver(a) => write!(f, "overcard ({})", a.to_char()),
+            DrawHand::FlashDraw(suit) => write!(f, "flush draw ({})", suit.to_char()),
+            DrawHand::BackdoorFlashDraw(suit) => {
+                write!(f, "backdoor flush draw ({})", suit.to_char())
+            }
+            DrawHand::StraightDraw(value) => write!(f, "straight draw ({})", value.to_char()),
+            DrawHand::BackDoorStraightDraw(a, b) => {
+                write!(f, "backdoor straight draw ({}{})", a.to_char(), b.to_char())
+            }
+        }
+    }
+}
+
+/// Draws with the cards that complete them, and the chance of hitting any of them.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Outs {
+    pub draws: Vec<(DrawHand, Vec<Card>)>,
+    /// Every out once, even if it completes more than one draw.
+    pub cards: Vec<Card>,
+    /// Chance of hitting on the turn, only known on the flop.
+    pub turn: Option<f64>,
+    /// Chance of hitting by the river, `None` on the river.
+    pub river: Option<f64>,
+}
+
+/// The cards that complete `draw`, leaving out the `known` cards. Backdoor draws need two
+/// more cards and so have no single-card outs.
+pub fn outs(draw: &DrawHand, known: &[Card]) -> Vec<Card> {
+    Value::values()
+        .iter()
+        .flat_map(|&value| {
+            Suit::suits()
+                .to_vec()
+                .into_iter()
+                .map(move |suit| Card { value, suit })
+        })
+        .filter(|card| !known.contains(card))
+        .filter(|card| match *draw {
+            DrawHand::FlashDraw(suit) => card.suit == suit,
+            DrawHand::StraightDraw(value) | DrawHand::OneOver(value) => card.value == value,
+            DrawHand::TwoOver(high, low) => card.value == high || card.value == low,
+            DrawHand::BackdoorFlashDraw(_) | DrawHand::BackDoorStraightDraw(_, _) => false,
+        })
+        .collect()
+}
+
+/// Outs of every draw given hero's hole cards and the board as `known`.
+pub fn count_outs(draws: &[DrawHand], known: &[Card]) -> Outs {
+    let draws = draws
+        .iter()
+        .map(|draw| (*draw, outs(draw, known)))
+        .collect::<Vec<_>>();
+    let cards = draws
+        .iter()
+        .flat_map(|(_, outs)| outs.iter().copied())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>();
+    let unseen = 52 - known.len();
+    let to_come = 7_usize.saturating_sub(known.len());
+    Outs {
+        turn: if to_come == 2 {
+            Some(hit_probability(cards.len(), unseen, 1))
+        } else {
+            None
+        },
+        river: if to_come > 0 {
+            Some(hit_probability(cards.len(), unseen, to_come))
+        } else {
+            None
+        },
+        draws,
+        cards,
+    }
+}
+
+/// Chance that at least one of `outs` among `unseen` cards comes in `dealt` cards.
+pub fn hit_probability(outs: usize, unseen: usize, dealt: usize) -> f64 {
+    let miss = (0..dealt).fold(1.0, |miss, i| {
+        miss * (unseen - outs - i) as f64 / (unseen - i) as f64
+    });
+    1.0 - miss
+}
+
 pub fn calculate_straight_draws(values: Vec<Value>) -> Vec<DrawHand> {
     let straight_draws = straight::get_straight_patterns()
         .into_iter()
-        .map(|pattern| {
+        .filter_map(|pattern| {
             let (matched, unmatched): (Vec<Value>, Vec<Value>) =
                 pattern.iter().partition(|value| values.contains(value));
             match matched.len() {
@@ -24,30 +116,23 @@ pub fn calculate_straight_draws(values: Vec<Value>) -> Vec<DrawHand> {
                 _ => None,
             }
         })
-        .filter_map(|el| el)
         .collect::<Vec<_>>();
     let straight_draw: Vec<DrawHand> = straight_draws
         .clone()
         .into_iter()
-        .filter(|&draw_hand| match draw_hand {
-            DrawHand::StraightDraw(_n) => true,
-            _ => false,
-        })
+        .filter(|&draw_hand| matches!(draw_hand, DrawHand::StraightDraw(_n)))
         .collect();
-    if straight_draw.len() > 0 {
+    if !straight_draw.is_empty() {
         return straight_draw;
     }
     let back_door_straight_draw: Vec<DrawHand> = straight_draws
         .into_iter()
-        .filter(|&draw_hand| match draw_hand {
-            DrawHand::BackDoorStraightDraw(_n, _m) => true,
-            _ => false,
-        })
+        .filter(|&draw_hand| matches!(draw_hand, DrawHand::BackDoorStraightDraw(_n, _m)))
         .collect();
-    if back_door_straight_draw.len() > 0 {
+    if !back_door_straight_draw.is_empty() {
         return back_door_straight_draw;
     }
-    return vec![];
+    vec![]
 }
 
 #[cfg(test)]
@@ -72,6 +157,47 @@ mod tests {
         assert_eq!(actual[1], straight_draw_eight);
     }
 
+    #[test]
+    fn test_count_outs() {
+        // Open-ended straight draw plus a flush draw: 9 hearts and 6 more straight cards.
+        let cards = Cards::from_str("Th9h8c7h2h").unwrap();
+        let outs = count_outs(&cards.analyse_drawhand(), cards.cards());
+        assert_eq!(
+            outs.draws
+                .iter()
+                .map(|(draw, outs)| (*draw, outs.len()))
+                .collect::<Vec<_>>(),
+            vec![
+                (DrawHand::FlashDraw(Suit::Heart), 9),
+                (DrawHand::StraightDraw(Value::Six), 4),
+                (DrawHand::StraightDraw(Value::Jack), 4),
+            ]
+        );
+        assert_eq!(outs.cards.len(), 15);
+        assert!(!outs.cards.contains(&Card {
+            value: Value::Two,
+            suit: Suit::Heart
+        }));
+        assert_eq!(format!("{:.3}", outs.turn.unwrap()), "0.319");
+        assert_eq!(format!("{:.3}", outs.river.unwrap()), "0.541");
+    }
+
+    #[test]
+    fn test_count_outs_on_turn_and_river() {
+        let cards = Cards::from_str("AhKh7h2h9c3s").unwrap();
+        let outs = count_outs(&cards.analyse_drawhand(), cards.cards());
+        assert_eq!(outs.cards.len(), 9);
+        assert_eq!(outs.turn, None);
+        assert_eq!(
+            format!("{:.4}", outs.river.unwrap()),
+            format!("{:.4}", 9.0 / 46.0)
+        );
+
+        let river = count_outs(&[], Cards::from_str("AhKh7h2h9c3sJd").unwrap().cards());
+        assert_eq!(river.river, None);
+        assert!(river.cards.is_empty());
+    }
+
     #[test]
     fn test_gutshot_straight_draws() {
         let values = Cards::from_str("2hQh9sJcKd").ok().unwrap().values();
@@ -0,0 +1,200 @@
+use itertools::Itertools;
+use std::collections::BTreeMap;
+
+use rust_poker::{
+    constants::CARD_COUNT,
+    hand_evaluator::{evaluate, Hand, CARDS},
+    hand_range::{Combo, HandRange},
+};
+
+/// Boards with at most this many runouts are enumerated exactly by default (turn and river).
+pub const EXACT_RUNOUT_LIMIT: usize = 44;
+
+/// A single hand against a range is cheap enough to enumerate exactly from the flop on.
+pub const HAND_EXACT_RUNOUT_LIMIT: usize = 990;
+
+const BOARD_CARDS: usize = 5;
+
+/// Hero's share of the pot split into wins, ties and losses.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Equity {
+    pub win: f64,
+    pub tie: f64,
+    pub lose: f64,
+}
+
+impl Equity {
+    /// Hero's equity, counting a tie as half the pot.
+    pub fn equity(&self) -> f64 {
+        self.win + self.tie / 2.0
+    }
+}
+
+/// Number of distinct runouts left for a board of `board_len` cards with two hands dealt.
+pub fn runout_count(board_len: usize) -> usize {
+    let rest = BOARD_CARDS.saturating_sub(board_len);
+    let deck = CARD_COUNT as usize - board_len - 4;
+    (0..rest).fold(1, |acc, i| acc * (deck - i) / (i + 1))
+}
+
+/// Enumerates every runout for every hero/villain combo pair, weighting pairs by combo weight.
+pub fn exact_equity(
+    hero_hand_range: &HandRange,
+    villain_hand_range: &HandRange,
+    board_mask: u64,
+) -> Result<Equity, &'static str> {
+    let board_len = board_mask.count_ones() as usize;
+    if board_len > BOARD_CARDS {
+        return Err("Too many board cards");
+    }
+    let board = Hand::from_bit_mask(board_mask);
+
+    let mut total = Equity::default();
+    let mut total_weight = 0.0;
+    for hero in live_combos(hero_hand_range, board_mask) {
+        for villain in live_combos(villain_hand_range, board_mask) {
+            let dead = board_mask | combo_mask(hero) | combo_mask(villain);
+            if dead.count_ones() as usize != board_len + 4 {
+                continue;
+            }
+            let weight = f64::from(hero.2) * f64::from(villain.2);
+            let equity = enumerate_runouts(board, dead, hero, villain, BOARD_CARDS - board_len);
+            total.win += equity.win * weight;
+            total.tie += equity.tie * weight;
+            total.lose += equity.lose * weight;
+            total_weight += weight;
+        }
+    }
+    if total_weight == 0.0 {
+        return Err("conflicting ranges");
+    }
+    Ok(Equity {
+        win: total.win / total_weight,
+        tie: total.tie / total_weight,
+        lose: total.lose / total_weight,
+    })
+}
+
+fn enumerate_runouts(board: Hand, dead: u64, hero: &Combo, villain: &Combo, rest: usize) -> Equity {
+    let hero_hand = board + CARDS[usize::from(hero.0)] + CARDS[usize::from(hero.1)];
+    let villain_hand = board + CARDS[usize::from(villain.0)] + CARDS[usize::from(villain.1)];
+    let (mut win, mut tie, mut lose) = (0_u64, 0_u64, 0_u64);
+    (0..CARD_COUNT as usize)
+        .filter(|card| dead & (1_u64 << card) == 0)
+        .combinations(rest)
+        .for_each(|runout| {
+            let (hero_score, villain_score) = runout
+                .into_iter()
+                .fold((hero_hand, villain_hand), |(hero, villain), card| {
+                    (hero + CARDS[card], villain + CARDS[card])
+                });
+            let hero_score = evaluate(&hero_score);
+            let villain_score = evaluate(&villain_score);
+            match hero_score.cmp(&villain_score) {
+                std::cmp::Ordering::Greater => win += 1,
+                std::cmp::Ordering::Equal => tie += 1,
+                std::cmp::Ordering::Less => lose += 1,
+            }
+        });
+    let count = (win + tie + lose) as f64;
+    Equity {
+        win: win as f64 / count,
+        tie: tie as f64 / count,
+        lose: lose as f64 / count,
+    }
+}
+
+/// Splits a range into parts keyed by `key`, dropping combos that use a card in `dead_mask`.
+pub fn split_range<K: Ord>(
+    hand_range: &HandRange,
+    dead_mask: u64,
+    key: impl Fn(&Combo) -> K,
+) -> BTreeMap<K, HandRange> {
+    live_combos(hand_range, dead_mask)
+        .into_iter()
+        .fold(BTreeMap::new(), |mut acc, combo| {
+            acc.entry(key(combo))
+                .or_insert_with(|| HandRange {
+                    hands: vec![],
+                    char_vec: vec![],
+                })
+                .hands
+                .push(*combo);
+            acc
+        })
+}
+
+fn live_combos(hand_range: &HandRange, board_mask: u64) -> Vec<&Combo> {
+    hand_range
+        .hands
+        .iter()
+        .filter(|combo| combo.2 > 0 && combo_mask(combo) & board_mask == 0)
+        .collect()
+}
+
+fn combo_mask(combo: &Combo) -> u64 {
+    (1_u64 << combo.0) | (1_u64 << combo.1)
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_poker::hand_range::get_card_mask;
+
+    use super::*;
+    use crate::core::hand_class::HandClass;
+
+    fn range(text: &str) -> HandRange {
+        HandRange::from_string(text.to_string())
+    }
+
+    #[test]
+    fn test_runout_count() {
+        assert_eq!(runout_count(5), 1);
+        assert_eq!(runout_count(4), 44);
+        assert_eq!(runout_count(3), 990);
+    }
+
+    #[test]
+    fn test_exact_equity_on_river() {
+        let equity = exact_equity(&range("AhAd"), &range("KhKd"), get_card_mask("2c7s9dTcJs"));
+        assert_eq!(
+            equity,
+            Ok(Equity {
+                win: 1.0,
+                tie: 0.0,
+                lose: 0.0
+            })
+        );
+    }
+
+    #[test]
+    fn test_exact_equity_split_pot() {
+        let equity = exact_equity(&range("2h3d"), &range("2d3h"), get_card_mask("AsKsQsJcTc"));
+        assert_eq!(equity.unwrap().equity(), 0.5);
+    }
+
+    #[test]
+    fn test_exact_equity_on_turn() {
+        // Villain's set of kings is only outdrawn by the two remaining aces.
+        let equity =
+            exact_equity(&range("AhAd"), &range("KhKd"), get_card_mask("Kc7s2d3c")).unwrap();
+        assert_eq!(equity.win, 2.0 / 44.0);
+        assert_eq!(equity.lose, 42.0 / 44.0);
+    }
+
+    #[test]
+    fn test_split_range() {
+        let parts = split_range(&range("AKs,QQ"), get_card_mask("AhQc"), |combo| {
+            HandClass::from_combo(combo).to_string()
+        });
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts["AKs"].hands.len(), 3);
+        assert_eq!(parts["QQ"].hands.len(), 3);
+    }
+
+    #[test]
+    fn test_exact_equity_conflicting_ranges() {
+        let equity = exact_equity(&range("AhAd"), &range("AhAd"), get_card_mask("2c7s9dTcJs"));
+        assert!(equity.is_err());
+    }
+}
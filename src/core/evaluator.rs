@@ -0,0 +1,291 @@
+use std::{collections::HashMap, sync::OnceLock};
+
+use itertools::Itertools;
+use rs_poker::core::{Card, Suit, Value};
+
+use crate::core::{cards::Cards, madehand::MadeHand};
+
+/// Strength of the best 5-card hand, from 1 for 7-5-4-3-2 offsuit to 7,462 for a royal flush.
+/// Equal ranks tie at showdown.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub struct HandRank(u16);
+
+impl HandRank {
+    pub fn value(self) -> u16 {
+        self.0
+    }
+
+    pub fn made_hand(self) -> MadeHand {
+        tables().classes[usize::from(self.0 - 1)]
+    }
+}
+
+/// Ranks 5 to 7 cards with two table lookups: one by the rank mask of a suit with five or
+/// more cards, otherwise one by a perfect hash of how many cards of each rank there are.
+pub fn evaluate(cards: &[Card]) -> Result<HandRank, &'static str> {
+    if !(5..=7).contains(&cards.len()) {
+        return Err("Invalid cards length");
+    }
+    let mut seen = 0_u64;
+    let mut counts = [0_u8; 13];
+    let mut suits = [0_u16; 4];
+    for card in cards {
+        let (value, suit) = (card.value as usize, card.suit as usize);
+        seen |= 1 << (value * 4 + suit);
+        counts[value] += 1;
+        suits[suit] |= 1 << value;
+    }
+    if seen.count_ones() as usize != cards.len() {
+        return Err("Duplicate cards");
+    }
+
+    let tables = tables();
+    // Five cards of a suit out of seven leave no room for quads or a full house.
+    if let Some(mask) = suits.iter().find(|mask| mask.count_ones() >= 5) {
+        return Ok(HandRank(tables.flush[usize::from(*mask)]));
+    }
+    let hash = tables.hash(&counts, cards.len());
+    Ok(HandRank(tables.unsuited[cards.len()][hash]))
+}
+
+/// One 5-card hand for each of the 7,462 hand classes: every multiset of ranks, plus a suited
+/// version of each set of five different ranks.
+pub(crate) fn hand_classes() -> Vec<Vec<Card>> {
+    let suits = Suit::suits();
+    Value::values()
+        .iter()
+        .combinations_with_replacement(5)
+        .filter(|values| values.iter().counts().values().all(|count| *count <= 4))
+        .flat_map(|values| {
+            // Same ranks get different suits; different ranks rotate suits so they never
+            // make a flush.
+            let unsuited = values
+                .iter()
+                .enumerate()
+                .map(|(i, value)| {
+                    let same = values[..i].iter().filter(|v| *v == value).count();
+                    let ranks_before = values[..i].iter().unique().filter(|v| *v != value).count();
+                    Card {
+                        value: **value,
+                        suit: suits[(same + ranks_before) % 4],
+                    }
+                })
+                .collect::<Vec<_>>();
+            let suited = values
+                .iter()
+                .map(|value| Card {
+                    value: **value,
+                    suit: Suit::Heart,
+                })
+                .collect::<Vec<_>>();
+            if values.iter().unique().count() == 5 {
+                vec![unsuited, suited]
+            } else {
+                vec![unsuited]
+            }
+        })
+        .collect()
+}
+
+struct Tables {
+    /// Made hand of every rank, rank 1 first.
+    classes: Vec<MadeHand>,
+    /// Best rank by the 13-bit rank mask of a suit with five to seven cards.
+    flush: Vec<u16>,
+    /// Best rank without a flush by card count, then by `hash` of the rank counts.
+    unsuited: Vec<Vec<u16>>,
+    /// `offsets[ranks][cards][count]`: how many rank counts sort before one whose first of
+    /// `ranks` ranks has `count` cards, with `cards` cards in those ranks.
+    offsets: Vec<Vec<[usize; 5]>>,
+}
+
+impl Tables {
+    /// Position of the rank counts among all counts of `cards` cards, no rank more than four.
+    fn hash(&self, counts: &[u8; 13], cards: usize) -> usize {
+        let mut left = cards;
+        let mut hash = 0;
+        for (i, count) in counts.iter().enumerate() {
+            hash += self.offsets[13 - i][left][usize::from(*count)];
+            left -= usize::from(*count);
+        }
+        hash
+    }
+
+    fn build() -> Self {
+        // ways[ranks][cards]: rank counts of `cards` cards over `ranks` ranks.
+        let mut ways = vec![vec![0_usize; 8]; 14];
+        ways[0][0] = 1;
+        for ranks in 1..=13 {
+            for cards in 0..8 {
+                ways[ranks][cards] = (0..=cards.min(4)).map(|c| ways[ranks - 1][cards - c]).sum();
+            }
+        }
+        let offsets = (0..=13)
+            .map(|ranks| {
+                (0..8_usize)
+                    .map(|cards| {
+                        let mut offset = [0; 5];
+                        for count in 1..5 {
+                            let fewer = match (ranks, cards.checked_sub(count - 1)) {
+                                (0, _) | (_, None) => 0,
+                                (_, Some(rest)) => ways[ranks - 1][rest],
+                            };
+                            offset[count] = offset[count - 1] + fewer;
+                        }
+                        offset
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let hands = hand_classes()
+            .into_iter()
+            .map(|hand| {
+                let cards = Cards::new(hand).unwrap();
+                (cards.enumerate_madehand().unwrap(), cards.cards().to_vec())
+            })
+            .sorted_by(|a, b| a.0.cmp(&b.0))
+            .collect::<Vec<_>>();
+        let classes = hands.iter().map(|(madehand, _)| *madehand).collect();
+        let rank_of = hands
+            .iter()
+            .enumerate()
+            .map(|(i, (madehand, _))| (*madehand, i as u16 + 1))
+            .collect::<HashMap<_, _>>();
+
+        let mut tables = Tables {
+            classes,
+            flush: vec![0; 1 << 13],
+            unsuited: (0..8).map(|cards| vec![0; ways[13][cards]]).collect(),
+            offsets,
+        };
+
+        for (madehand, hand) in hands.iter() {
+            let rank = rank_of[madehand];
+            if hand.iter().map(|card| card.suit).all_equal() {
+                let mask = hand
+                    .iter()
+                    .fold(0, |mask, card| mask | 1 << card.value as usize);
+                tables.flush[mask] = rank;
+            } else {
+                let hash = tables.hash(&rank_counts(hand), 5);
+                tables.unsuited[5][hash] = rank;
+            }
+        }
+        // Six and seven cards: the best hand left after taking away any one card.
+        for cards in 6..=7 {
+            for mask in (0..1_usize << 13).filter(|mask| mask.count_ones() as usize == cards) {
+                tables.flush[mask] = (0..13)
+                    .filter(|bit| mask & 1 << bit != 0)
+                    .map(|bit| tables.flush[mask & !(1 << bit)])
+                    .max()
+                    .unwrap();
+            }
+            for counts in all_counts(cards) {
+                let best = (0..13)
+                    .filter(|i| counts[*i] > 0)
+                    .map(|i| {
+                        let mut fewer = counts;
+                        fewer[i] -= 1;
+                        tables.unsuited[cards - 1][tables.hash(&fewer, cards - 1)]
+                    })
+                    .max()
+                    .unwrap();
+                let hash = tables.hash(&counts, cards);
+                tables.unsuited[cards][hash] = best;
+            }
+        }
+        tables
+    }
+}
+
+fn tables() -> &'static Tables {
+    static TABLES: OnceLock<Tables> = OnceLock::new();
+    TABLES.get_or_init(Tables::build)
+}
+
+fn rank_counts(cards: &[Card]) -> [u8; 13] {
+    cards.iter().fold([0; 13], |mut counts, card| {
+        counts[card.value as usize] += 1;
+        counts
+    })
+}
+
+/// Every way to spread `cards` cards over the 13 ranks, at most four of a rank.
+fn all_counts(cards: usize) -> Vec<[u8; 13]> {
+    Value::values()
+        .iter()
+        .combinations_with_replacement(cards)
+        .map(|values| {
+            values.iter().fold([0; 13], |mut counts, value| {
+                counts[**value as usize] += 1;
+                counts
+            })
+        })
+        .filter(|counts| counts.iter().all(|count| *count <= 4))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn test_hash_is_perfect() {
+        let tables = tables();
+        for cards in 5..=7 {
+            let hashes = all_counts(cards)
+                .iter()
+                .map(|counts| tables.hash(counts, cards))
+                .sorted()
+                .collect::<Vec<_>>();
+            assert_eq!(
+                hashes,
+                (0..tables.unsuited[cards].len()).collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let rank = |s: &str| evaluate(Cards::from_str(s).unwrap().cards()).unwrap();
+        assert_eq!(rank("7h5d4c3s2h").value(), 1);
+        assert_eq!(rank("AhKhQhJhTh").value(), 7462);
+        assert_eq!(rank("AhKhQhJhTh2c3d"), rank("AhKhQhJhTh"));
+        assert!(rank("8h7h2c7s9d") > rank("AhKd2c7s9d"));
+        assert_eq!(
+            rank("KsKdKh2c2dQh7s").made_hand(),
+            MadeHand::FullHouse(Value::King, Value::Two)
+        );
+        let mut duplicate = Cards::from_str("Ah2c3d4s").unwrap().cards().to_vec();
+        duplicate.push(duplicate[0]);
+        assert!(evaluate(&duplicate).is_err());
+        assert!(evaluate(Cards::from_str("AhKd2c3d").unwrap().cards()).is_err());
+    }
+
+    #[test]
+    fn test_same_as_combinations() {
+        // Every 6-card hand of a few suits' worth of cards, against the combination path.
+        let deck = Value::values()
+            .iter()
+            .filter(|value| **value >= Value::Eight)
+            .cartesian_product([Suit::Heart, Suit::Spade].iter())
+            .map(|(value, suit)| Card {
+                value: *value,
+                suit: *suit,
+            })
+            .chain(Cards::from_str("2c5d8d").unwrap().cards().to_vec())
+            .collect::<Vec<_>>();
+        for hand in deck.into_iter().combinations(6) {
+            let cards = Cards::new(hand).unwrap();
+            assert_eq!(
+                cards.analyze_madehand(),
+                cards.enumerate_madehand(),
+                "{}",
+                cards
+            );
+        }
+    }
+}
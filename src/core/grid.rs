@@ -0,0 +1,92 @@
+use rust_poker::{constants::RANK_COUNT, hand_range::HandRange};
+
+use crate::core::hand_class::HandClass;
+
+/// Number of two-card combos in a deck.
+pub const TOTAL_COMBOS: usize = 1326;
+
+const IN_RANGE: &str = "\x1b[30;42m";
+const PARTIAL: &str = "\x1b[30;43m";
+const RESET: &str = "\x1b[0m";
+
+/// Renders the 13x13 chart: pairs on the diagonal, suited above it and offsuit below.
+///
+/// With `color`, classes fully in range are green, and those partly in range or played at a
+/// mixed frequency are yellow.
+/// Without it, they are marked with `*` and `~`.
+pub fn render_grid(hand_range: &HandRange, color: bool) -> String {
+    let ranks = (0..RANK_COUNT).rev().collect::<Vec<u8>>();
+    ranks
+        .iter()
+        .map(|&row| {
+            ranks
+                .iter()
+                .map(|&column| {
+                    let class = HandClass {
+                        high: row.max(column),
+                        low: row.min(column),
+                        suited: row > column,
+                    };
+                    render_cell(&class, class_frequency(hand_range, &class), color)
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Share of all 1326 combos that `hand_range` covers, weighted by frequency.
+pub fn combo_percentage(hand_range: &HandRange) -> f64 {
+    let weight: u32 = hand_range
+        .hands
+        .iter()
+        .map(|combo| u32::from(combo.2))
+        .sum();
+    f64::from(weight) / TOTAL_COMBOS as f64
+}
+
+/// Share of the class's combos in range, weighted by frequency.
+fn class_frequency(hand_range: &HandRange, class: &HandClass) -> f64 {
+    let weight: u32 = hand_range
+        .hands
+        .iter()
+        .filter(|combo| &HandClass::from_combo(combo) == class)
+        .map(|combo| u32::from(combo.2))
+        .sum();
+    f64::from(weight) / (100 * class.combos()) as f64
+}
+
+fn render_cell(class: &HandClass, frequency: f64, color: bool) -> String {
+    let label = format!("{:<3}", class.to_string());
+    match (frequency > 0.0, frequency >= 1.0, color) {
+        (false, _, _) => format!("{} ", label),
+        (_, true, true) => format!("{}{}{} ", IN_RANGE, label, RESET),
+        (_, false, true) => format!("{}{}{} ", PARTIAL, label, RESET),
+        (_, true, false) => format!("{}*", label),
+        (_, false, false) => format!("{}~", label),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_grid() {
+        let hand_range = HandRange::from_string("AA,AKs,AhKd,AQs@50".to_string());
+        let grid = render_grid(&hand_range, false);
+        let rows = grid.lines().collect::<Vec<_>>();
+        assert_eq!(rows.len(), 13);
+        assert!(rows[0].starts_with("AA *AKs*AQs~AJs "));
+        assert!(rows[1].starts_with("AKo~KK  "));
+        assert!(rows[12].ends_with("22  "));
+    }
+
+    #[test]
+    fn test_combo_percentage() {
+        let hand_range = HandRange::from_string("random".to_string());
+        assert_eq!(combo_percentage(&hand_range), 100.0);
+        let hand_range = HandRange::from_string("AA@50".to_string());
+        assert_eq!(format!("{:.3}", combo_percentage(&hand_range)), "0.226");
+    }
+}
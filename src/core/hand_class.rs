@@ -0,0 +1,68 @@
+use std::fmt;
+
+use rust_poker::{constants::RANK_TO_CHAR, hand_range::Combo};
+
+/// One of the 169 starting hand classes such as "AKs", "AKo" or "QQ".
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, PartialOrd, Ord)]
+pub struct HandClass {
+    /// Rank index (0 = deuce, 12 = ace) of the higher card.
+    pub high: u8,
+    /// Rank index of the lower card.
+    pub low: u8,
+    pub suited: bool,
+}
+
+impl HandClass {
+    pub fn from_combo(combo: &Combo) -> Self {
+        let (rank1, rank2) = (combo.0 >> 2, combo.1 >> 2);
+        HandClass {
+            high: rank1.max(rank2),
+            low: rank1.min(rank2),
+            suited: (combo.0 & 3) == (combo.1 & 3),
+        }
+    }
+
+    pub fn is_pair(&self) -> bool {
+        self.high == self.low
+    }
+
+    /// Number of card combinations in the class before card removal.
+    pub fn combos(&self) -> usize {
+        match (self.is_pair(), self.suited) {
+            (true, _) => 6,
+            (false, true) => 4,
+            (false, false) => 12,
+        }
+    }
+}
+
+impl fmt::Display for HandClass {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let high = RANK_TO_CHAR[usize::from(self.high)];
+        let low = RANK_TO_CHAR[usize::from(self.low)];
+        match (self.is_pair(), self.suited) {
+            (true, _) => write!(f, "{}{}", high, low),
+            (false, true) => write!(f, "{}{}s", high, low),
+            (false, false) => write!(f, "{}{}o", high, low),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_poker::hand_range::HandRange;
+
+    use super::*;
+
+    fn class_of(text: &str) -> HandClass {
+        HandClass::from_combo(&HandRange::from_string(text.to_string()).hands[0])
+    }
+
+    #[test]
+    fn test_from_combo() {
+        assert_eq!(class_of("AhKh").to_string(), "AKs");
+        assert_eq!(class_of("Kd9c").to_string(), "K9o");
+        assert_eq!(class_of("7s7c").to_string(), "77");
+        assert_eq!(class_of("2h3h").combos(), 4);
+    }
+}
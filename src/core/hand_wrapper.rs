@@ -13,13 +13,26 @@ pub struct HandWrapper {
 impl FromStr for HandWrapper {
     type Err = String;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let cards = Cards::from_str(s);
-        match cards {
-            Err(message) => Err(message.to_string()),
-            Ok(cards) => Hand::new_from_str(s).and_then(|hand| {
-                let hand_wrapper = HandWrapper { hand, cards };
-                Ok(hand_wrapper)
-            }),
+        let cards = Cards::from_str(s).map_err(|message| message.to_string())?;
+        if cards.len() != 2 {
+            return Err(format!("A hand is 2 cards, got {}", cards.len()));
         }
+        Hand::new_from_str(&cards.to_string()).map(|hand| HandWrapper { hand, cards })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!(
+            HandWrapper::from_str("[Ah Kd]").unwrap().cards.to_string(),
+            "AhKd"
+        );
+        assert!(HandWrapper::from_str("AhAh").is_err());
+        assert!(HandWrapper::from_str("AhKdQc").is_err());
+        assert!(HandWrapper::from_str("AhK").is_err());
     }
 }
@@ -0,0 +1,568 @@
+use std::{fmt, str::FromStr};
+
+use crate::core::{
+    action::Action,
+    position::{Position, TableSize},
+};
+
+/// What one player does in a preflop line. Raises are numbered by how many raises the pot has
+/// seen including this one, so an open is `Raise(1)` and a 3bet `Raise(2)`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Move {
+    Limp,
+    Call,
+    Raise(u8),
+}
+
+/// The most raises a line can have, i.e. a 5bet.
+const MAX_RAISES: u8 = 4;
+
+/// Open size in bb for raises the line doesn't size.
+pub const DEFAULT_OPEN_SIZE: f64 = 2.5;
+
+/// Unsized 3bets and 4bets raise to this many times the previous raise.
+pub const DEFAULT_RERAISE: f64 = 3.0;
+
+const SMALL_BLIND: f64 = 0.5;
+const BIG_BLIND: f64 = 1.0;
+
+/// One action of a preflop line, with the raise size in bb if it is known.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Step {
+    pub position: Position,
+    pub action: Move,
+    pub size: Option<f64>,
+}
+
+/// The preflop action in order, such as "utg-open co-call btn-3bet".
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct PreflopLine {
+    steps: Vec<Step>,
+}
+
+impl PreflopLine {
+    /// The line of a chart for `me` taking `action` against `opponent`.
+    pub fn from_spot(me: Position, opponent: Position, action: Action) -> Self {
+        match action {
+            Action::Limp => PreflopLine::default().then(me, Move::Limp),
+            action => PreflopLine::heads_up(me, opponent, action.to_hero_action())
+                .through(me)
+                .unwrap_or_default(),
+        }
+    }
+
+    /// The whole line of a heads-up pot where villain's last action was `villain_action`.
+    pub fn heads_up(hero: Position, villain: Position, villain_action: Action) -> Self {
+        let line = PreflopLine::default();
+        match villain_action {
+            Action::Open => line.then(villain, Move::Raise(1)).then(hero, Move::Call),
+            Action::Limp => line.then(villain, Move::Limp).then(hero, Move::Limp),
+            Action::Call => line.then(hero, Move::Raise(1)).then(villain, Move::Call),
+            Action::ThreeBet => line
+                .then(hero, Move::Raise(1))
+                .then(villain, Move::Raise(2))
+                .then(hero, Move::Call),
+            Action::ThreeBetCall => line
+                .then(villain, Move::Raise(1))
+                .then(hero, Move::Raise(2))
+                .then(villain, Move::Call),
+            Action::FourBet => line
+                .then(villain, Move::Raise(1))
+                .then(hero, Move::Raise(2))
+                .then(villain, Move::Raise(3))
+                .then(hero, Move::Call),
+            Action::FourBetCall => line
+                .then(hero, Move::Raise(1))
+                .then(villain, Move::Raise(2))
+                .then(hero, Move::Raise(3))
+                .then(villain, Move::Call),
+        }
+    }
+
+    fn then(mut self, position: Position, action: Move) -> Self {
+        self.steps.push(Step {
+            position,
+            action,
+            size: None,
+        });
+        self
+    }
+
+    pub fn steps(&self) -> &[Step] {
+        &self.steps
+    }
+
+    /// The players in the order they first act.
+    pub fn players(&self) -> Vec<Position> {
+        self.steps.iter().fold(vec![], |mut players, step| {
+            if !players.contains(&step.position) {
+                players.push(step.position);
+            }
+            players
+        })
+    }
+
+    /// The line up to and including the last action of `position`.
+    pub fn through(&self, position: Position) -> Option<PreflopLine> {
+        self.steps
+            .iter()
+            .rposition(|step| step.position == position)
+            .map(|last| PreflopLine {
+                steps: self.steps[..=last].to_vec(),
+            })
+    }
+
+    /// Sizes the unsized raises with `sizes`, given in bb for the open, the 3bet and so on.
+    pub fn with_sizes(&self, sizes: &[f64]) -> PreflopLine {
+        PreflopLine {
+            steps: self
+                .steps
+                .iter()
+                .map(|step| match (step.action, step.size) {
+                    (Move::Raise(level), None) => Step {
+                        size: sizes.get(usize::from(level) - 1).copied(),
+                        ..*step
+                    },
+                    _ => *step,
+                })
+                .collect(),
+        }
+    }
+
+    /// Whether a chart keyed on this line applies to `other`: the same actions, and every
+    /// raise this line sizes is sized the same in `other` if `other` sizes it at all.
+    pub fn fits(&self, other: &PreflopLine) -> bool {
+        self.same_actions(other)
+            && self
+                .steps
+                .iter()
+                .zip(other.steps.iter())
+                .all(|(a, b)| match (a.size, b.size) {
+                    (Some(a), Some(b)) => (a - b).abs() < 1e-9,
+                    _ => true,
+                })
+    }
+
+    /// How many raises both lines give the same size for.
+    pub fn matching_sizes(&self, other: &PreflopLine) -> usize {
+        self.steps
+            .iter()
+            .zip(other.steps.iter())
+            .filter(|(a, b)| matches!((a.size, b.size), (Some(a), Some(b)) if (a - b).abs() < 1e-9))
+            .count()
+    }
+
+    /// How many raises the line gives a size for.
+    pub fn sized_raises(&self) -> usize {
+        self.steps.iter().filter(|step| step.size.is_some()).count()
+    }
+
+    /// What each player has put in by the end of the line, in bb and capped at `stack`,
+    /// including the blinds. Unsized raises use `DEFAULT_OPEN_SIZE` and `DEFAULT_RERAISE`.
+    pub fn contributions(&self, stack: f64) -> Vec<(Position, f64)> {
+        let mut put_in = vec![(Position::SB, SMALL_BLIND), (Position::BB, BIG_BLIND)];
+        let mut bet = BIG_BLIND;
+        for step in self.steps.iter() {
+            let amount = match step.action {
+                Move::Limp => BIG_BLIND,
+                Move::Call => bet,
+                Move::Raise(level) => {
+                    bet = step.size.unwrap_or(if level == 1 {
+                        DEFAULT_OPEN_SIZE
+                    } else {
+                        bet * DEFAULT_RERAISE
+                    });
+                    bet
+                }
+            }
+            .min(stack);
+            match put_in
+                .iter_mut()
+                .find(|(position, _)| position == &step.position)
+            {
+                Some((_, chips)) => *chips = chips.max(amount),
+                None => put_in.push((step.position, amount)),
+            }
+        }
+        put_in
+    }
+
+    /// The pot in bb at the end of the line.
+    pub fn pot(&self, stack: f64) -> f64 {
+        self.contributions(stack)
+            .iter()
+            .map(|(_, chips)| chips)
+            .sum()
+    }
+
+    /// The line with `positions` calling the last raise, or limping if nobody raised, so
+    /// that everyone in the pot has matched the bet. After the last raise, the callers and
+    /// the players already in the line act in seat order, starting left of the raiser.
+    pub fn with_callers(&self, positions: &[Position], table: TableSize) -> PreflopLine {
+        let action = match self.last_raiser() {
+            Some(_) => Move::Call,
+            None => Move::Limp,
+        };
+        let raise = self
+            .steps
+            .iter()
+            .rposition(|step| matches!(step.action, Move::Raise(_)));
+        let seats = table.seats();
+        let first = raise.map_or(0, |raise| {
+            seats
+                .iter()
+                .position(|seat| seat == &self.steps[raise].position)
+                .map_or(0, |seat| seat + 1)
+        });
+        let order = |position: &Position| {
+            seats
+                .iter()
+                .position(|seat| seat == position)
+                .map_or(usize::MAX, |seat| {
+                    (seat + seats.len() - first) % seats.len()
+                })
+        };
+        let start = raise.map_or(0, |raise| raise + 1);
+        let mut after = self.steps[start..].to_vec();
+        after.extend(positions.iter().map(|position| Step {
+            position: *position,
+            action,
+            size: None,
+        }));
+        after.sort_by_key(|step| order(&step.position));
+        PreflopLine {
+            steps: self.steps[..start].iter().copied().chain(after).collect(),
+        }
+    }
+
+    /// The player who made the last raise, if anyone raised.
+    pub fn last_raiser(&self) -> Option<Position> {
+        self.steps
+            .iter()
+            .rev()
+            .find(|step| matches!(step.action, Move::Raise(_)))
+            .map(|step| step.position)
+    }
+
+    /// Same players taking the same actions, whatever the sizes.
+    pub fn same_actions(&self, other: &PreflopLine) -> bool {
+        self.steps.len() == other.steps.len()
+            && self
+                .steps
+                .iter()
+                .zip(other.steps.iter())
+                .all(|(a, b)| a.position == b.position && a.action == b.action)
+    }
+
+    /// The last player's spot as (me, opponent, action): the opponent is the last raiser
+    /// before them. `None` for an empty line or a 5bet, which no `Action` describes.
+    pub fn spot(&self) -> Option<(Position, Position, Action)> {
+        let (last, before) = self.steps.split_last()?;
+        let raiser = before
+            .iter()
+            .rev()
+            .find(|step| matches!(step.action, Move::Raise(_)));
+        let opponent = raiser.map_or(Position::NONE, |step| step.position);
+        let action = match (last.action, raiser.map(|step| step.action)) {
+            (Move::Limp, _) => Action::Limp,
+            (Move::Raise(1), _) => Action::Open,
+            (Move::Raise(2), _) => Action::ThreeBet,
+            (Move::Raise(3), _) => Action::FourBet,
+            (Move::Call, Some(Move::Raise(1))) => Action::Call,
+            (Move::Call, Some(Move::Raise(2))) => Action::ThreeBetCall,
+            (Move::Call, Some(Move::Raise(3))) => Action::FourBetCall,
+            _ => return None,
+        };
+        match action {
+            Action::Open | Action::Limp => Some((last.position, Position::NONE, action)),
+            action => Some((last.position, opponent, action)),
+        }
+    }
+
+    /// The lines to look the last player's chart up with, most specific first: the line
+    /// itself, then with seats replaced by IP/OOP if heads-up, then the generic limp chart.
+    pub fn chart_keys(&self, table: TableSize) -> Vec<PreflopLine> {
+        let mut keys = vec![self.clone()];
+        if let [first, second] = self.players()[..] {
+            if table.has_seat(first) && table.has_seat(second) {
+                keys.push(PreflopLine {
+                    steps: self
+                        .steps
+                        .iter()
+                        .map(|step| {
+                            let villain = if step.position == first {
+                                second
+                            } else {
+                                first
+                            };
+                            Step {
+                                position: step.position.convert_to_ip_or_oop(villain, table),
+                                ..*step
+                            }
+                        })
+                        .collect(),
+                });
+            }
+        }
+        if self.steps.last().map(|step| step.action) == Some(Move::Limp) {
+            keys.push(PreflopLine::default().then(Position::NONE, Move::Limp));
+        }
+        keys
+    }
+}
+
+impl FromStr for PreflopLine {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut raises = 0;
+        let steps = s
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|token| !token.is_empty())
+            .map(|token| {
+                let step = parse_step(token, raises)?;
+                if let Move::Raise(level) = step.action {
+                    raises = level;
+                }
+                Ok(step)
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        if steps.is_empty() {
+            return Err(format!("Invalid line: {:?}", s));
+        }
+        Ok(PreflopLine { steps })
+    }
+}
+
+fn parse_step(token: &str, raises: u8) -> Result<Step, String> {
+    let invalid = |reason: &str| format!("Invalid line: {:?}: {}", token, reason);
+    let parts = token.split('-').collect::<Vec<_>>();
+    let (position, action, size) = match parts[..] {
+        [position, action] => (position, action, None),
+        [position, action, size] => (position, action, Some(size)),
+        _ => return Err(invalid("expected POSITION-ACTION[-SIZE]")),
+    };
+    let position = Position::from_str(position).map_err(|err| invalid(&err))?;
+    let size = match size.map(f64::from_str) {
+        Some(Ok(size)) if size > 0.0 => Some(size),
+        Some(_) => return Err(invalid("size must be a positive number of bb")),
+        None => None,
+    };
+    let action = match &action.to_ascii_lowercase()[..] {
+        "limp" if raises == 0 => Move::Limp,
+        "call" if raises > 0 => Move::Call,
+        "open" if raises == 0 => Move::Raise(1),
+        "raise" if raises < MAX_RAISES => Move::Raise(raises + 1),
+        "3bet" if raises == 1 => Move::Raise(2),
+        "4bet" if raises == 2 => Move::Raise(3),
+        "5bet" if raises == 3 => Move::Raise(4),
+        "limp" | "call" | "open" | "raise" | "3bet" | "4bet" | "5bet" => {
+            return Err(invalid(&format!(
+                "can't {} after {} raises",
+                action, raises
+            )))
+        }
+        _ => return Err(invalid(&format!("unknown action {}", action))),
+    };
+    if size.is_some() && !matches!(action, Move::Raise(_)) {
+        return Err(invalid("only raises have a size"));
+    }
+    Ok(Step {
+        position,
+        action,
+        size,
+    })
+}
+
+impl fmt::Display for PreflopLine {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut limped = false;
+        let steps = self
+            .steps
+            .iter()
+            .map(|step| {
+                let action = match step.action {
+                    Move::Limp => "limp".to_string(),
+                    Move::Call => "call".to_string(),
+                    Move::Raise(1) if limped => "raise".to_string(),
+                    Move::Raise(1) => "open".to_string(),
+                    Move::Raise(level) => format!("{}bet", level + 1),
+                };
+                limped |= step.action == Move::Limp;
+                let position = step.position.to_string().to_ascii_lowercase();
+                match step.size {
+                    Some(size) => format!("{}-{}-{}", position, action, size),
+                    None => format!("{}-{}", position, action),
+                }
+            })
+            .collect::<Vec<_>>();
+        write!(f, "{}", steps.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(text: &str) -> PreflopLine {
+        PreflopLine::from_str(text).unwrap()
+    }
+
+    #[test]
+    fn test_from_str() {
+        let squeeze = line("utg-open co-call btn-3bet");
+        assert_eq!(
+            squeeze.players(),
+            vec![Position::UTG, Position::CO, Position::BTN]
+        );
+        assert_eq!(squeeze.steps()[2].action, Move::Raise(2));
+        assert_eq!(squeeze.to_string(), "utg-open co-call btn-3bet");
+
+        let limp_raise = line("SB-limp BB-raise-4 sb-call");
+        assert_eq!(limp_raise.steps()[1].action, Move::Raise(1));
+        assert_eq!(limp_raise.steps()[1].size, Some(4.0));
+        assert_eq!(limp_raise.to_string(), "sb-limp bb-raise-4 sb-call");
+
+        assert_eq!(line("utg+1-open-2.5").to_string(), "utg+1-open-2.5");
+    }
+
+    #[test]
+    fn test_from_str_invalid() {
+        assert!(PreflopLine::from_str("").is_err());
+        assert!(PreflopLine::from_str("co-call").is_err());
+        assert!(PreflopLine::from_str("utg-open co-4bet").is_err());
+        assert!(PreflopLine::from_str("utg-open co-open").is_err());
+        assert!(PreflopLine::from_str("utg-fold").is_err());
+        assert!(PreflopLine::from_str("xx-open").is_err());
+        assert!(PreflopLine::from_str("utg-open-0").is_err());
+    }
+
+    #[test]
+    fn test_spot() {
+        use Position::*;
+        let cases = [
+            ("btn-open", (BTN, NONE, Action::Open)),
+            ("co-open btn-call", (BTN, CO, Action::Call)),
+            ("utg-open co-call btn-3bet", (BTN, UTG, Action::ThreeBet)),
+            ("co-open btn-3bet co-call", (CO, BTN, Action::ThreeBetCall)),
+            ("co-open btn-3bet co-4bet", (CO, BTN, Action::FourBet)),
+            (
+                "co-open bb-3bet co-4bet bb-call",
+                (BB, CO, Action::FourBetCall),
+            ),
+            ("sb-limp bb-raise", (BB, NONE, Action::Open)),
+            ("sb-limp", (SB, NONE, Action::Limp)),
+        ];
+        for (text, spot) in cases.iter() {
+            assert_eq!(line(text).spot(), Some(*spot), "{}", text);
+        }
+        assert_eq!(line("co-open btn-3bet co-4bet btn-5bet").spot(), None);
+    }
+
+    #[test]
+    fn test_from_spot() {
+        use Position::*;
+        let cases = [
+            (BTN, NONE, Action::Open, "btn-open"),
+            (BB, BTN, Action::Call, "btn-open bb-call"),
+            (BTN, CO, Action::ThreeBet, "co-open btn-3bet"),
+            (IP, OOP, Action::ThreeBetCall, "ip-open oop-3bet ip-call"),
+            (IP, OOP, Action::FourBet, "ip-open oop-3bet ip-4bet"),
+            (
+                OOP,
+                IP,
+                Action::FourBetCall,
+                "ip-open oop-3bet ip-4bet oop-call",
+            ),
+            (NONE, NONE, Action::Limp, "none-limp"),
+        ];
+        for (me, opponent, action, text) in cases.iter() {
+            let from_spot = PreflopLine::from_spot(*me, *opponent, *action);
+            assert_eq!(from_spot.to_string(), *text);
+            assert_eq!(from_spot.spot(), Some((*me, *opponent, *action)));
+        }
+    }
+
+    #[test]
+    fn test_sizes() {
+        let sized = line("co-open-2.5 btn-3bet-8");
+        assert!(sized.fits(&line("co-open-2.5 btn-3bet-8")));
+        assert!(!sized.fits(&line("co-open-3 btn-3bet-8")));
+        assert!(sized.fits(&line("co-open btn-3bet")));
+        assert!(line("co-open btn-3bet").fits(&sized));
+        assert_eq!(sized.sized_raises(), 2);
+        assert_eq!(sized.matching_sizes(&line("co-open-2.5 btn-3bet")), 1);
+        assert!(PreflopLine::from_str("co-open btn-call-2.5").is_err());
+
+        let filled = line("co-open btn-3bet-9 co-call").with_sizes(&[2.0, 7.0]);
+        assert_eq!(filled.to_string(), "co-open-2 btn-3bet-9 co-call");
+    }
+
+    #[test]
+    fn test_pot() {
+        // Blinds fold: 2.5 + 2.5 + 1.5 dead.
+        assert_eq!(line("co-open btn-call").pot(100.0), 6.5);
+        assert_eq!(line("co-open-2 btn-3bet-7 co-call").pot(100.0), 15.5);
+        // Defaults: 2.5 open, 7.5 3bet, 22.5 4bet.
+        assert_eq!(line("btn-open bb-3bet btn-4bet bb-call").pot(100.0), 45.5);
+        assert_eq!(line("btn-open bb-3bet btn-4bet bb-call").pot(20.0), 40.5);
+        assert_eq!(line("sb-limp bb-raise-4 sb-call").pot(100.0), 8.0);
+        let contributions = line("utg-open co-call bb-3bet-12 utg-call").contributions(100.0);
+        assert_eq!(
+            contributions,
+            vec![
+                (Position::SB, 0.5),
+                (Position::BB, 12.0),
+                (Position::UTG, 12.0),
+                (Position::CO, 2.5)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_through_and_chart_keys() {
+        let full = PreflopLine::heads_up(Position::BB, Position::BTN, Action::ThreeBetCall);
+        assert_eq!(full.to_string(), "btn-open bb-3bet btn-call");
+        assert_eq!(
+            full.through(Position::BB).unwrap().to_string(),
+            "btn-open bb-3bet"
+        );
+        assert_eq!(full.through(Position::CO), None);
+        assert_eq!(full.last_raiser(), Some(Position::BB));
+        assert_eq!(line("sb-limp").last_raiser(), None);
+        let six_max = TableSize::SixMax;
+        let multiway = line("co-open bb-call").with_callers(&[Position::BTN], six_max);
+        assert_eq!(multiway.to_string(), "co-open btn-call bb-call");
+        assert_eq!(multiway.pot(100.0), 8.0);
+        assert_eq!(
+            line("sb-limp")
+                .with_callers(&[Position::BTN], six_max)
+                .to_string(),
+            "btn-limp sb-limp"
+        );
+        // Callers given out of seat order act left of the last raiser first.
+        assert_eq!(
+            line("co-open btn-3bet co-call")
+                .with_callers(&[Position::UTG, Position::BB], six_max)
+                .to_string(),
+            "co-open btn-3bet bb-call utg-call co-call"
+        );
+
+        let keys = full
+            .chart_keys(TableSize::SixMax)
+            .iter()
+            .map(|key| key.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            keys,
+            vec!["btn-open bb-3bet btn-call", "ip-open oop-3bet ip-call"]
+        );
+        let keys = line("sb-limp").chart_keys(TableSize::SixMax);
+        assert_eq!(keys.last().unwrap().to_string(), "none-limp");
+        assert_eq!(
+            line("utg-open co-call btn-3bet")
+                .chart_keys(TableSize::SixMax)
+                .len(),
+            1
+        );
+    }
+}
@@ -1,23 +1,198 @@
+use std::fmt;
+
 use rs_poker::core::Value;
 
-#[derive(Debug, PartialEq, PartialOrd, Eq, Ord)]
+/// Best 5-card hand with every card that breaks ties, highest first. Ordered from the
+/// weakest hand to the strongest.
+#[derive(Debug, PartialEq, PartialOrd, Eq, Ord, Hash, Clone, Copy)]
 pub enum MadeHand {
     HighCard(Value, Value, Value, Value, Value),
     OnePair(Value, Value, Value, Value),
+    /// Higher pair, lower pair, kicker.
     TwoPair(Value, Value, Value),
     ThreeCard(Value, Value, Value),
+    /// High card of the straight, five for the wheel.
     Straight(Value),
-    Flush(Value),
+    Flush(Value, Value, Value, Value, Value),
+    /// Three of a kind, then the pair.
     FullHouse(Value, Value),
     FourCard(Value, Value),
     StraightFlush(Value),
     RoyalStraightFlush,
 }
 
-// #[cfg(test)]
-// mod tests {
-//     use rs_poker::core::Value;
-//     use rust_poker::hand_range::HandRange;
+impl fmt::Display for MadeHand {
+    /// The hand's name and the ranks of its five cards, e.g. "two pair (KK77A)". Part of the
+    /// JSON reports, so keep it stable.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (name, values) = match *self {
+            MadeHand::HighCard(a, b, c, d, e) => ("high card", vec![a, b, c, d, e]),
+            MadeHand::OnePair(p, a, b, c) => ("one pair", vec![p, p, a, b, c]),
+            MadeHand::TwoPair(p, q, a) => ("two pair", vec![p, p, q, q, a]),
+            MadeHand::ThreeCard(t, a, b) => ("three of a kind", vec![t, t, t, a, b]),
+            MadeHand::Straight(high) => ("straight", straight(high)),
+            MadeHand::Flush(a, b, c, d, e) => ("flush", vec![a, b, c, d, e]),
+            MadeHand::FullHouse(t, p) => ("full house", vec![t, t, t, p, p]),
+            MadeHand::FourCard(q, a) => ("four of a kind", vec![q, q, q, q, a]),
+            MadeHand::StraightFlush(high) => ("straight flush", straight(high)),
+            MadeHand::RoyalStraightFlush => ("royal flush", straight(Value::Ace)),
+        };
+        let values = values
+            .iter()
+            .map(|value| value.to_char())
+            .collect::<String>();
+        write!(f, "{} ({})", name, values)
+    }
+}
+
+/// Ranks of the straight to `high`, the wheel's ace last.
+fn straight(high: Value) -> Vec<Value> {
+    (0..5)
+        .map(|i| Value::from_u8((high as u8 + 13 - i) % 13))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cmp::Ordering, collections::HashSet, str::FromStr};
+
+    use itertools::Itertools;
+    use rs_poker::core::{Card, Rankable, Suit, Value};
+
+    use super::MadeHand;
+    use crate::core::{cards::Cards, evaluator};
 
-//     use super::MadeHand;
-// }
+    fn cards(cards: Vec<Card>) -> Cards {
+        Cards::new(cards).unwrap()
+    }
+
+    #[test]
+    fn test_every_hand_class() {
+        let hands = evaluator::hand_classes()
+            .into_iter()
+            .map(|hand| {
+                let madehand = cards(hand.clone()).enumerate_madehand().unwrap();
+                (madehand, hand)
+            })
+            .sorted_by(|a, b| a.0.cmp(&b.0))
+            .collect::<Vec<_>>();
+        assert_eq!(hands.len(), 7462);
+        assert_eq!(
+            hands
+                .iter()
+                .map(|(madehand, _)| madehand)
+                .collect::<HashSet<_>>()
+                .len(),
+            7462
+        );
+        // Ordered like rs_poker ranks them, with no two classes tied.
+        for pair in hands.windows(2) {
+            assert!(
+                pair[0].1.rank_five() < pair[1].1.rank_five(),
+                "{:?} < {:?}",
+                pair[0].0,
+                pair[1].0
+            );
+        }
+
+        let count = |f: fn(&MadeHand) -> bool| hands.iter().filter(|(m, _)| f(m)).count();
+        assert_eq!(count(|m| matches!(m, MadeHand::HighCard(..))), 1277);
+        assert_eq!(count(|m| matches!(m, MadeHand::OnePair(..))), 2860);
+        assert_eq!(count(|m| matches!(m, MadeHand::TwoPair(..))), 858);
+        assert_eq!(count(|m| matches!(m, MadeHand::ThreeCard(..))), 858);
+        assert_eq!(count(|m| matches!(m, MadeHand::Straight(..))), 10);
+        assert_eq!(count(|m| matches!(m, MadeHand::Flush(..))), 1277);
+        assert_eq!(count(|m| matches!(m, MadeHand::FullHouse(..))), 156);
+        assert_eq!(count(|m| matches!(m, MadeHand::FourCard(..))), 156);
+        assert_eq!(
+            count(|m| matches!(
+                m,
+                MadeHand::StraightFlush(..) | MadeHand::RoyalStraightFlush
+            )),
+            10
+        );
+    }
+
+    #[test]
+    fn test_kickers() {
+        let madehand = |s: &str| Cards::from_str(s).unwrap().analyze_madehand().unwrap();
+        assert_eq!(
+            madehand("AhJh9h4h2hKc3d"),
+            MadeHand::Flush(
+                Value::Ace,
+                Value::Jack,
+                Value::Nine,
+                Value::Four,
+                Value::Two
+            )
+        );
+        assert_eq!(
+            madehand("8h7h2c7s9d"),
+            MadeHand::OnePair(Value::Seven, Value::Nine, Value::Eight, Value::Two)
+        );
+        assert_eq!(
+            madehand("KsKdKh2c2dQh7s"),
+            MadeHand::FullHouse(Value::King, Value::Two)
+        );
+        assert_eq!(
+            madehand("9s9d9h9cAdAh7s"),
+            MadeHand::FourCard(Value::Nine, Value::Ace)
+        );
+        assert_eq!(madehand("As2d3h4c5s5d"), MadeHand::Straight(Value::Five));
+        // A straight and a flush in seven cards: the flush wins.
+        assert_eq!(
+            madehand("6h7h8s9hTcKh2h"),
+            MadeHand::Flush(
+                Value::King,
+                Value::Nine,
+                Value::Seven,
+                Value::Six,
+                Value::Two
+            )
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        let madehand = |s: &str| Cards::from_str(s).unwrap().analyze_madehand().unwrap();
+        assert_eq!(madehand("KsKd7h7cAd").to_string(), "two pair (KK77A)");
+        assert_eq!(madehand("As2d3h4c5s").to_string(), "straight (5432A)");
+        assert_eq!(madehand("AhKhQhJhTh").to_string(), "royal flush (AKQJT)");
+    }
+
+    #[test]
+    fn test_seven_cards_like_rs_poker() {
+        // Deterministic sample of 7-card hands compared pairwise with rs_poker.
+        let deck = Value::values()
+            .iter()
+            .cartesian_product(Suit::suits().iter())
+            .map(|(value, suit)| Card {
+                value: *value,
+                suit: *suit,
+            })
+            .collect::<Vec<_>>();
+        let mut seed = 12345_u64;
+        let mut next = |n: usize| {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) as usize % n
+        };
+        let hands = (0..500)
+            .map(|_| {
+                let mut deck = deck.clone();
+                (0..7)
+                    .map(|_| deck.remove(next(deck.len())))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        for pair in hands.windows(2) {
+            let ours = cards(pair[0].clone())
+                .analyze_madehand()
+                .unwrap()
+                .cmp(&cards(pair[1].clone()).analyze_madehand().unwrap());
+            let theirs: Ordering = pair[0].rank().cmp(&pair[1].rank());
+            assert_eq!(ours, theirs, "{:?} vs {:?}", pair[0], pair[1]);
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use std::fmt;
+
+/// Whether calling the bet is profitable.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Verdict {
+    Call,
+    Fold,
+}
+
+impl Verdict {
+    fn from_profitable(profitable: bool) -> Self {
+        if profitable {
+            Verdict::Call
+        } else {
+            Verdict::Fold
+        }
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Verdict::Call => write!(f, "call"),
+            Verdict::Fold => write!(f, "fold"),
+        }
+    }
+}
+
+/// Share of the final pot hero needs to win to break even when calling `bet` into `pot`.
+/// `pot` is the pot before the bet, so calling wins `pot + bet` for a price of `bet`.
+pub fn break_even_equity(pot: f64, bet: f64) -> f64 {
+    bet / (pot + 2.0 * bet)
+}
+
+pub fn verdict(equity: f64, pot: f64, bet: f64) -> Verdict {
+    Verdict::from_profitable(equity >= break_even_equity(pot, bet))
+}
+
+/// How much more hero must win on later streets for calling with `equity` to break even,
+/// 0.0 when the pot already lays the price.
+pub fn implied_odds_needed(equity: f64, pot: f64, bet: f64) -> f64 {
+    if equity <= 0.0 {
+        return f64::INFINITY;
+    }
+    (bet / equity - (pot + 2.0 * bet)).max(0.0)
+}
+
+/// Whether villain's `stack_behind` after the call can pay what the implied odds need.
+pub fn implied_verdict(equity: f64, pot: f64, bet: f64, stack_behind: f64) -> Verdict {
+    Verdict::from_profitable(implied_odds_needed(equity, pot, bet) <= stack_behind)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_break_even_equity() {
+        // Calling 4 to win a 14.5 pot.
+        assert_eq!(
+            format!("{:.3}", break_even_equity(6.5, 4.0)),
+            format!("{:.3}", 4.0 / 14.5)
+        );
+        assert_eq!(break_even_equity(10.0, 10.0), 1.0 / 3.0);
+        assert_eq!(verdict(0.35, 10.0, 10.0), Verdict::Call);
+        assert_eq!(verdict(0.3, 10.0, 10.0), Verdict::Fold);
+    }
+
+    #[test]
+    fn test_implied_odds() {
+        // A flush draw with 20% equity facing a pot-sized bet needs 20 more later.
+        assert_eq!(implied_odds_needed(0.2, 10.0, 10.0), 20.0);
+        assert_eq!(implied_odds_needed(0.5, 10.0, 10.0), 0.0);
+        assert_eq!(implied_verdict(0.2, 10.0, 10.0, 80.0), Verdict::Call);
+        assert_eq!(implied_verdict(0.2, 10.0, 10.0, 15.0), Verdict::Fold);
+        assert_eq!(implied_verdict(0.0, 10.0, 10.0, 80.0), Verdict::Fold);
+    }
+}
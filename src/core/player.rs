@@ -1,100 +1,188 @@
-use crate::core::{action::Action, position::Position};
-use anyhow::{Context, Result};
+use crate::core::{
+    action::Action,
+    line::PreflopLine,
+    position::{Position, TableSize},
+    profile::Profile,
+    range::{self, Range},
+};
+use anyhow::Result;
 use rs_poker::core::Hand;
 use rust_poker::hand_range::HandRange;
-use serde::{Deserialize, Serialize};
-use std::{fs::File, io::BufReader, str::FromStr};
 
 #[derive(Debug)]
 pub struct Player {
-    hand: Option<Hand>,
+    pub hand: Option<Hand>,
     pub hand_range: HandRange,
-    action: Action,
-    position: Position,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-pub struct Patterns {
-    patterns: Vec<Pattern>,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-pub struct Pattern {
-    action: String,
-    opponent: String,
-    me: String,
-    hands: String,
-    name: String,
+    pub range_name: String,
+    /// Combos in the chart before card removal.
+    pub range_combos: usize,
+    pub action: Action,
+    pub position: Position,
+    pub profile: Profile,
+    pub table: TableSize,
 }
 
 impl Player {
+    /// The player at `position` after `line`, with the chart for their last action in it at
+    /// an effective `stack` in bb.
     pub fn new(
+        ranges: &[Range],
         hand: Option<Hand>,
-        hero_position: Position,
-        villain_positon: Position,
-        villain_action: Action,
+        line: &PreflopLine,
+        position: Position,
+        profile: Profile,
+        table: TableSize,
+        stack: f64,
     ) -> Result<Self> {
-        let (hero_position, villain_positon, hero_action) =
-            collect_position_and_action(hero_position, villain_positon, villain_action);
-        get_hand_range(&hero_position, &villain_positon, &hero_action).and_then(|hand_range| {
-            Ok(Self {
-                hand,
-                hand_range,
-                action: hero_action,
-                position: hero_position,
-            })
+        if let Some(seat) = line.players().iter().find(|seat| !table.has_seat(**seat)) {
+            anyhow::bail!("no {} seat at {}", seat, table);
+        }
+        let line = line
+            .through(position)
+            .ok_or_else(|| anyhow::anyhow!("{} doesn't act in {}", position, line))?;
+        get_hand_range(ranges, &line, &profile, table, stack).map(|range| Self {
+            hand,
+            hand_range: range.hand_range().clone(),
+            range_name: range.name().to_string(),
+            range_combos: range.hand_range().hands.len(),
+            action: range.action(),
+            position: if range.me().is_none() {
+                position
+            } else {
+                range.me()
+            },
+            profile,
+            table,
         })
     }
-}
 
-fn collect_position_and_action(
-    hero_position: Position,
-    villain_position: Position,
-    villain_action: Action,
-) -> (Position, Position, Action) {
-    let hero_action = villain_action.to_hero_action();
-    if hero_action.is_after_three_bet() {
-        let hero_position = hero_position.convert_to_ip_or_oop(villain_position);
-        let villain_position = hero_position.invert();
-        return (hero_position, villain_position, hero_action);
+    /// Removes the combos blocked by `dead_mask`, i.e. the board and any known hole cards.
+    pub fn remove_cards(&mut self, dead_mask: u64) {
+        self.hand_range.remove_conflicting_combos(dead_mask);
     }
-    if hero_action.eq(&Action::Open) {
-        return (hero_position, Position::NONE, hero_action);
+
+    pub fn live_combos(&self) -> usize {
+        self.hand_range.hands.len()
     }
-    (hero_position, villain_position, hero_action)
 }
 
-fn get_hand_range(
-    hero_position: &Position,
-    villain_positon: &Position,
-    hero_action: &Action,
-) -> Result<HandRange> {
-    let json = load_json()?;
-    let pattern = json.patterns.into_iter().find(|p| {
-        if !Position::from_str(&p.me).unwrap().eq(hero_position) {
-            return false;
-        }
-        if !Position::from_str(&p.opponent).unwrap().eq(villain_positon) {
-            return false;
-        }
-        if !Action::from_str(&p.action).unwrap().eq(hero_action) {
-            return false;
-        }
-        true
-    });
-    match pattern {
-        Some(pattern) => Ok(HandRange::from_string(pattern.hands)),
+/// The chart for the last player of `line`. Heads-up pots fall back to the IP/OOP charts,
+/// decided by the postflop acting order.
+fn get_hand_range<'a>(
+    ranges: &'a [Range],
+    line: &PreflopLine,
+    profile: &Profile,
+    table: TableSize,
+    stack: f64,
+) -> Result<&'a Range> {
+    match range::find_line_range(ranges, line, profile, table, Some(stack)) {
+        Some(range) => Ok(range),
         None => Err(anyhow::anyhow!(format!(
-            "can't find HandRange. {:?} vs. {:?} : {:?}",
-            hero_position, villain_positon, hero_action
+            "can't find HandRange. {} ({}, {}, {}bb)",
+            line, profile, table, stack
         ))),
     }
 }
 
-fn load_json() -> Result<Patterns> {
-    let file = File::open("range.json").context("can't load json file".to_string())?;
-    let reader = BufReader::new(file);
-    let deserialized: Patterns =
-        serde_json::from_reader(reader).with_context(|| "can't read json file".to_string())?;
-    Ok(deserialized)
+#[cfg(test)]
+mod tests {
+    use std::{path::Path, str::FromStr};
+
+    use rust_poker::hand_range::get_card_mask;
+
+    use super::*;
+
+    #[test]
+    fn test_remove_cards() {
+        let ranges = range::read_ranges(Some(Path::new("range.json"))).unwrap();
+        let mut player = Player::new(
+            &ranges,
+            None,
+            &PreflopLine::heads_up(Position::BTN, Position::CO, Action::ThreeBetCall),
+            Position::BTN,
+            Profile::Standard,
+            TableSize::SixMax,
+            100.0,
+        )
+        .unwrap();
+        assert_eq!(player.range_name, "BTN3bet vs CO");
+        assert_eq!(player.range_combos, 114);
+
+        player.remove_cards(get_card_mask("AhKd"));
+        assert!(player
+            .hand_range
+            .hands
+            .iter()
+            .all(|combo| !combo.to_string().contains("Ah") && !combo.to_string().contains("Kd")));
+        assert_eq!(player.range_combos, 114);
+        assert_eq!(player.live_combos(), 87);
+    }
+
+    #[test]
+    fn test_heads_up_chart() {
+        use Position::*;
+        let ranges = range::read_ranges(Some(Path::new("range.json"))).unwrap();
+        let cases = [
+            (BTN, BB, Action::ThreeBet, (IP, Action::ThreeBetCall)),
+            (BB, BTN, Action::ThreeBetCall, (BB, Action::ThreeBet)),
+            (SB, BB, Action::ThreeBet, (OOP, Action::ThreeBetCall)),
+            (BB, SB, Action::FourBet, (IP, Action::FourBetCall)),
+            (CO, BTN, Action::ThreeBet, (OOP, Action::ThreeBetCall)),
+            (SB, BTN, Action::FourBetCall, (OOP, Action::FourBet)),
+            (UTG, MP, Action::Call, (UTG, Action::Open)),
+            (BB, CO, Action::Open, (BB, Action::Call)),
+            (SB, BB, Action::Limp, (SB, Action::Limp)),
+        ];
+        for (hero, villain, villain_action, expected) in cases.iter() {
+            let line = PreflopLine::heads_up(*hero, *villain, *villain_action);
+            let player = Player::new(
+                &ranges,
+                None,
+                &line,
+                *hero,
+                Profile::Standard,
+                TableSize::SixMax,
+                100.0,
+            )
+            .unwrap();
+            assert_eq!((player.position, player.action), *expected, "{}", line);
+        }
+    }
+
+    #[test]
+    fn test_line_chart() {
+        let ranges = range::read_ranges(Some(Path::new("range.json"))).unwrap();
+        let line = PreflopLine::from_str("utg-open co-call btn-3bet").unwrap();
+        let chart = |position| {
+            Player::new(
+                &ranges,
+                None,
+                &line,
+                position,
+                Profile::Standard,
+                TableSize::SixMax,
+                100.0,
+            )
+            .map(|player| player.range_name)
+        };
+        assert_eq!(chart(Position::CO).unwrap(), "COコール vs UTG(EP)");
+        // No squeeze chart in the built-in ranges.
+        assert!(chart(Position::BTN).is_err());
+        assert!(chart(Position::SB).is_err());
+    }
+
+    #[test]
+    fn test_seat_not_at_table() {
+        let ranges = range::read_ranges(Some(Path::new("range.json"))).unwrap();
+        let player = Player::new(
+            &ranges,
+            None,
+            &PreflopLine::heads_up(Position::HJ, Position::CO, Action::Open),
+            Position::HJ,
+            Profile::Standard,
+            TableSize::SixMax,
+            100.0,
+        );
+        assert_eq!(player.unwrap_err().to_string(), "no HJ seat at 6max");
+    }
 }
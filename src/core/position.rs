@@ -1,10 +1,14 @@
 use std::{fmt, str::FromStr};
-#[derive(Debug, PartialEq, PartialOrd, Clone, Copy)]
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub enum Position {
     SB,
     BB,
     UTG,
+    UTG1,
+    UTG2,
+    LJ,
     MP,
+    HJ,
     CO,
     BTN,
     OOP,
@@ -12,6 +16,87 @@ pub enum Position {
     NONE,
 }
 
+/// When the seats act: preflop the blinds act last, postflop they act first.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Round {
+    Preflop,
+    Postflop,
+}
+
+/// Number of seats at the table. Range charts are keyed by it, and it decides which
+/// seats exist and the order they act in.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum TableSize {
+    ThreeMax,
+    FourMax,
+    FiveMax,
+    #[default]
+    SixMax,
+    NineMax,
+}
+
+impl TableSize {
+    /// The seats in preflop acting order.
+    pub fn seats(&self) -> &'static [Position] {
+        use Position::*;
+        match self {
+            TableSize::ThreeMax => &[BTN, SB, BB],
+            TableSize::FourMax => &[CO, BTN, SB, BB],
+            TableSize::FiveMax => &[HJ, CO, BTN, SB, BB],
+            TableSize::SixMax => &[UTG, MP, CO, BTN, SB, BB],
+            TableSize::NineMax => &[UTG, UTG1, UTG2, LJ, HJ, CO, BTN, SB, BB],
+        }
+    }
+
+    /// The seats in the order they act in `round`.
+    pub fn acting_order(&self, round: Round) -> Vec<Position> {
+        match round {
+            Round::Preflop => self.seats().to_vec(),
+            Round::Postflop => self.postflop_order(),
+        }
+    }
+
+    /// The seats in postflop acting order: the blinds first, the button last.
+    pub fn postflop_order(&self) -> Vec<Position> {
+        let (blinds, rest): (Vec<Position>, Vec<Position>) = self
+            .seats()
+            .iter()
+            .partition(|seat| [Position::SB, Position::BB].contains(seat));
+        blinds.into_iter().chain(rest).collect()
+    }
+
+    pub fn has_seat(&self, position: Position) -> bool {
+        self.seats().contains(&position)
+    }
+}
+
+impl FromStr for TableSize {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match &s.to_ascii_lowercase().replace('-', "")[..] {
+            "3max" => Ok(Self::ThreeMax),
+            "4max" => Ok(Self::FourMax),
+            "5max" => Ok(Self::FiveMax),
+            "6max" => Ok(Self::SixMax),
+            "9max" | "fullring" => Ok(Self::NineMax),
+            _ => Err(format!("Invalid table size: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for TableSize {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let string = match *self {
+            TableSize::ThreeMax => "3max",
+            TableSize::FourMax => "4max",
+            TableSize::FiveMax => "5max",
+            TableSize::SixMax => "6max",
+            TableSize::NineMax => "9max",
+        };
+        write!(f, "{}", string)
+    }
+}
+
 impl Position {
     pub fn from(s: &str) -> Option<Position> {
         match Position::from_str(s) {
@@ -33,7 +118,11 @@ impl FromStr for Position {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match &s.to_ascii_uppercase()[..] {
             "UTG" => Ok(Self::UTG),
+            "UTG+1" | "UTG1" => Ok(Self::UTG1),
+            "UTG+2" | "UTG2" => Ok(Self::UTG2),
+            "LJ" => Ok(Self::LJ),
             "MP" => Ok(Self::MP),
+            "HJ" => Ok(Self::HJ),
             "CO" => Ok(Self::CO),
             "BTN" => Ok(Self::BTN),
             "SB" => Ok(Self::SB),
@@ -41,34 +130,49 @@ impl FromStr for Position {
             "OOP" => Ok(Self::OOP),
             "IP" => Ok(Self::IP),
             "NONE" => Ok(Self::NONE),
-            _ => return Err(format!("Invalid position: {}", s)),
+            _ => Err(format!("Invalid position: {}", s)),
         }
     }
 }
 
 impl fmt::Display for Position {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let string = match self {
-            &Position::BB => "BB",
-            &Position::UTG => "UTG",
-            &Position::MP => "MP",
-            &Position::CO => "CO",
-            &Position::BTN => "BTN",
-            &Position::SB => "SB",
-            &Position::OOP => "OOP",
-            &Position::IP => "IP",
-            &Position::NONE => "NONE",
+        let string = match *self {
+            Position::BB => "BB",
+            Position::UTG => "UTG",
+            Position::UTG1 => "UTG+1",
+            Position::UTG2 => "UTG+2",
+            Position::LJ => "LJ",
+            Position::MP => "MP",
+            Position::HJ => "HJ",
+            Position::CO => "CO",
+            Position::BTN => "BTN",
+            Position::SB => "SB",
+            Position::OOP => "OOP",
+            Position::IP => "IP",
+            Position::NONE => "NONE",
         };
         write!(f, "{}", string)
     }
 }
 
 impl Position {
-    pub fn convert_to_ip_or_oop(&self, villain_positon: Position) -> Position {
-        if self > &villain_positon {
-            Position::OOP
-        } else {
-            Position::IP
+    /// Whether this seat acts after `other` in `round`.
+    /// `None` if either seat isn't at `table` or both are the same seat.
+    pub fn acts_after(&self, other: Position, round: Round, table: TableSize) -> Option<bool> {
+        let order = table.acting_order(round);
+        let seat = |position: Position| order.iter().position(|seat| seat == &position);
+        match (seat(*self), seat(other)) {
+            (Some(me), Some(other)) if me != other => Some(me > other),
+            _ => None,
+        }
+    }
+
+    /// IP if this seat acts after `villain_positon` postflop at `table`, OOP otherwise.
+    pub fn convert_to_ip_or_oop(&self, villain_positon: Position, table: TableSize) -> Position {
+        match self.acts_after(villain_positon, Round::Postflop, table) {
+            Some(true) => Position::IP,
+            _ => Position::OOP,
         }
     }
 
@@ -76,7 +180,7 @@ impl Position {
         match self {
             Position::IP => Position::OOP,
             Position::OOP => Position::IP,
-            other => other.clone(),
+            other => *other,
         }
     }
 }
@@ -85,10 +189,128 @@ impl Position {
 mod tests {
     use std::str::FromStr;
 
-    use super::Position;
+    use super::{Position, Round, TableSize};
+
+    const TABLES: [TableSize; 5] = [
+        TableSize::ThreeMax,
+        TableSize::FourMax,
+        TableSize::FiveMax,
+        TableSize::SixMax,
+        TableSize::NineMax,
+    ];
 
     #[test]
     fn position_test() {
         assert_eq!(Position::from_str("btn"), Ok(Position::BTN));
+        assert_eq!(Position::from_str("utg+1"), Ok(Position::UTG1));
+        assert_eq!(Position::UTG2.to_string(), "UTG+2");
+    }
+
+    #[test]
+    fn table_size_test() {
+        assert_eq!(TableSize::from_str("9-max"), Ok(TableSize::NineMax));
+        assert_eq!(TableSize::from_str("4MAX"), Ok(TableSize::FourMax));
+        assert!(TableSize::from_str("10max").is_err());
+        assert!(TableSize::NineMax.has_seat(Position::LJ));
+        assert!(!TableSize::SixMax.has_seat(Position::HJ));
+    }
+
+    #[test]
+    fn postflop_order_test() {
+        use Position::*;
+        assert_eq!(TableSize::ThreeMax.postflop_order(), vec![SB, BB, BTN]);
+        assert_eq!(
+            TableSize::NineMax.postflop_order(),
+            vec![SB, BB, UTG, UTG1, UTG2, LJ, HJ, CO, BTN]
+        );
+        assert_eq!(
+            TableSize::FiveMax.acting_order(Round::Preflop),
+            vec![HJ, CO, BTN, SB, BB]
+        );
+    }
+
+    /// Every 6-max seat pair as (seat, other, acts after preflop, in position postflop).
+    #[test]
+    fn six_max_seat_pairs_test() {
+        use Position::*;
+        let cases = [
+            (SB, BB, false, false),
+            (SB, UTG, true, false),
+            (SB, MP, true, false),
+            (SB, CO, true, false),
+            (SB, BTN, true, false),
+            (BB, UTG, true, false),
+            (BB, MP, true, false),
+            (BB, CO, true, false),
+            (BB, BTN, true, false),
+            (UTG, MP, false, false),
+            (UTG, CO, false, false),
+            (UTG, BTN, false, false),
+            (MP, CO, false, false),
+            (MP, BTN, false, false),
+            (CO, BTN, false, false),
+        ];
+        let table = TableSize::SixMax;
+        for &(seat, other, preflop, postflop) in cases.iter() {
+            assert_eq!(seat.acts_after(other, Round::Preflop, table), Some(preflop));
+            assert_eq!(
+                other.acts_after(seat, Round::Preflop, table),
+                Some(!preflop)
+            );
+            let (ip, oop) = if postflop { (IP, OOP) } else { (OOP, IP) };
+            assert_eq!(
+                seat.convert_to_ip_or_oop(other, table),
+                ip,
+                "{} vs {}",
+                seat,
+                other
+            );
+            assert_eq!(
+                other.convert_to_ip_or_oop(seat, table),
+                oop,
+                "{} vs {}",
+                other,
+                seat
+            );
+        }
+        assert_eq!(cases.len(), 6 * 5 / 2);
+    }
+
+    /// Every seat pair at every table size has exactly one player in position, the button
+    /// is always in position and SB always out of position.
+    #[test]
+    fn all_seat_pairs_test() {
+        for table in TABLES.iter() {
+            let seats = table.seats();
+            for (i, seat) in seats.iter().enumerate() {
+                for other in seats[i + 1..].iter() {
+                    for round in [Round::Preflop, Round::Postflop].iter() {
+                        let after = seat.acts_after(*other, *round, *table);
+                        assert_eq!(other.acts_after(*seat, *round, *table), after.map(|a| !a));
+                    }
+                    let ip = seat.convert_to_ip_or_oop(*other, *table);
+                    assert_eq!(other.convert_to_ip_or_oop(*seat, *table), ip.invert());
+                    match (seat, other) {
+                        (Position::BTN, _) | (_, Position::SB) => assert_eq!(ip, Position::IP),
+                        (Position::SB, _) | (_, Position::BTN) => assert_eq!(ip, Position::OOP),
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn acts_after_unseated_test() {
+        use Position::*;
+        assert_eq!(HJ.acts_after(BTN, Round::Postflop, TableSize::SixMax), None);
+        assert_eq!(
+            BTN.acts_after(BTN, Round::Postflop, TableSize::SixMax),
+            None
+        );
+        assert_eq!(
+            UTG1.acts_after(BTN, Round::Preflop, TableSize::NineMax),
+            Some(false)
+        );
     }
 }
@@ -0,0 +1,56 @@
+use std::{fmt, str::FromStr};
+
+/// Playing style a range chart is built for, taken from the `option` key of range.json.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Default)]
+pub enum Profile {
+    #[default]
+    Standard,
+    Nit,
+    Custom(String),
+}
+
+impl Profile {
+    pub fn is_standard(&self) -> bool {
+        self == &Profile::Standard
+    }
+}
+
+impl FromStr for Profile {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match &s.to_ascii_lowercase()[..] {
+            "" => Err("Invalid profile: empty".to_string()),
+            "standard" => Ok(Self::Standard),
+            "nit" => Ok(Self::Nit),
+            custom => Ok(Self::Custom(custom.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Profile::Standard => write!(f, "standard"),
+            Profile::Nit => write!(f, "nit"),
+            Profile::Custom(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::Profile;
+
+    #[test]
+    fn profile_test() {
+        assert_eq!(Profile::from_str("NIT"), Ok(Profile::Nit));
+        assert_eq!(Profile::from_str("standard"), Ok(Profile::Standard));
+        assert_eq!(
+            Profile::from_str("lag"),
+            Ok(Profile::Custom("lag".to_string()))
+        );
+        assert!(Profile::from_str("").is_err());
+    }
+}
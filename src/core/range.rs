@@ -1,11 +1,32 @@
-use rs_poker::core::Hand;
+use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 
-use std::{fmt::format, fs::File, io::BufReader, str::FromStr};
+use std::{
+    collections::BTreeMap,
+    env, fmt,
+    fs::File,
+    io::BufReader,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
-use rust_poker::hand_range::{Combo, HandRange};
+use itertools::Itertools;
+use rust_poker::{
+    constants::RANK_COUNT,
+    hand_range::{char_to_rank, char_to_suit, Combo, HandRange},
+};
 
-use crate::core::{action::Action, position::Position};
+use crate::core::{
+    action::Action,
+    grid::TOTAL_COMBOS,
+    hand_class::HandClass,
+    line::PreflopLine,
+    position::{Position, TableSize},
+    profile::Profile,
+};
+
+/// The chart bundled into the binary, used when no range file is found.
+const BUILTIN_RANGES: &str = include_str!("../../range.json");
 
 #[derive(Debug)]
 pub struct Range {
@@ -13,11 +34,94 @@ pub struct Range {
     action: Action,
     me: Position,
     opponent: Position,
+    profile: Profile,
+    table: TableSize,
+    line: PreflopLine,
+    /// Effective stacks the chart is for, any depth when `None`.
+    stack: Option<StackDepth>,
     hand_range: HandRange,
-    hands: Vec<Hand>,
+}
+
+/// Effective stacks in bb from `min` up to, but not including, `max`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct StackDepth {
+    pub min: f64,
+    pub max: Option<f64>,
+}
+
+impl StackDepth {
+    pub fn contains(&self, stack: f64) -> bool {
+        self.min <= stack && self.max.is_none_or(|max| stack < max)
+    }
+}
+
+impl FromStr for StackDepth {
+    type Err = String;
+    /// "40-100" or "100+", in bb.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid stack depth: {}", s);
+        let bb = |text: &str| {
+            f64::from_str(text.trim().trim_end_matches("bb"))
+                .ok()
+                .filter(|bb| *bb >= 0.0)
+        };
+        let depth = match s.trim().split('-').collect::<Vec<_>>()[..] {
+            [min] if min.ends_with('+') => StackDepth {
+                min: bb(min.trim_end_matches('+')).ok_or_else(invalid)?,
+                max: None,
+            },
+            [min, max] => StackDepth {
+                min: bb(min).ok_or_else(invalid)?,
+                max: Some(bb(max).ok_or_else(invalid)?),
+            },
+            _ => return Err(invalid()),
+        };
+        match depth.max {
+            Some(max) if max <= depth.min => Err(invalid()),
+            _ => Ok(depth),
+        }
+    }
+}
+
+impl fmt::Display for StackDepth {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.max {
+            Some(max) => write!(f, "{}-{}bb", self.min, max),
+            None => write!(f, "{}bb+", self.min),
+        }
+    }
 }
 
 impl Range {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn hand_range(&self) -> &HandRange {
+        &self.hand_range
+    }
+
+    pub fn profile(&self) -> &Profile {
+        &self.profile
+    }
+
+    pub fn table(&self) -> TableSize {
+        self.table
+    }
+
+    pub fn me(&self) -> Position {
+        self.me
+    }
+
+    pub fn action(&self) -> Action {
+        self.action
+    }
+
+    /// The preflop line up to and including this chart's action.
+    pub fn line(&self) -> &PreflopLine {
+        &self.line
+    }
+
     pub fn equals_me(&self, position: Position) -> bool {
         self.me == position
     }
@@ -26,19 +130,49 @@ impl Range {
         [Position::IP, Position::OOP].contains(&self.me)
     }
 
+    pub fn matches(&self, me: Position, opponent: Position, action: Action) -> bool {
+        self.line
+            .same_actions(&PreflopLine::from_spot(me, opponent, action))
+    }
+
+    fn same_spot(&self, other: &Range) -> bool {
+        self.table == other.table && self.line == other.line && self.stack == other.stack
+    }
+
     pub fn contains_combo(&self, combo: &Combo) -> bool {
-        self.hand_range.hands.contains(&combo)
+        self.frequency(combo) > 0.0
+    }
+
+    /// How often the combo takes this action, from 0.0 to 1.0.
+    pub fn frequency(&self, combo: &Combo) -> f64 {
+        frequency(&self.hand_range, combo)
     }
 
-    pub fn to_string(&self, verbose: Option<bool>) -> String {
+    pub fn to_string(&self, _verbose: Option<bool>) -> String {
         let opponent = if self.opponent.is_none() {
             "".to_string()
         } else {
             format!("vs {}", self.opponent)
         };
-        format!("- {} {} {}", self.me, self.action, opponent)
-            .trim()
-            .to_string()
+        let profile = if self.profile.is_standard() {
+            "".to_string()
+        } else {
+            format!("({})", self.profile)
+        };
+        let line =
+            if self.matches(self.me, self.opponent, self.action) && self.line.sized_raises() == 0 {
+                "".to_string()
+            } else {
+                format!("[{}]", self.line)
+            };
+        let stack = self.stack.map_or("".to_string(), |stack| stack.to_string());
+        format!(
+            "- {} {} {} {} {} {}",
+            self.me, self.action, opponent, line, stack, profile
+        )
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
     }
 }
 
@@ -50,396 +184,539 @@ struct JsonModel {
 #[derive(Serialize, Deserialize)]
 struct Pattern {
     name: String,
+    /// `action`, `me` and `opponent` may be left out when `line` is given.
+    #[serde(default)]
     action: String,
+    #[serde(default)]
     me: String,
+    #[serde(default)]
     opponent: String,
+    /// Preflop line such as "utg-open co-call btn-3bet", for spots a single action can't key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    line: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    option: Option<String>,
+    /// Table size the chart is for, 6max when omitted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    table: Option<String>,
+    /// Effective stacks the chart is for, such as "40-100" or "100+" (bb).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    stack: Option<String>,
     hands: String,
 }
 
+/// A problem with one entry of a range file.
+#[derive(Debug, PartialEq, Clone)]
+pub struct RangeError {
+    /// Position of the entry in `patterns`, from 0.
+    pub index: usize,
+    pub name: String,
+    pub field: &'static str,
+    pub message: String,
+}
+
+impl fmt::Display for RangeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "#{} {}: {}: {}",
+            self.index, self.name, self.field, self.message
+        )
+    }
+}
+
 impl Pattern {
-    fn to_range(&self) -> Range {
-        let action = Action::from_str(&self.action);
-        let me = Position::from_str(&self.me);
-        let opponent = Position::from_str(&self.opponent);
-        let hand_range = HandRange::from_string(self.hands.clone());
-        let hands: Vec<Hand> = hand_range
-            .hands
-            .clone()
-            .into_iter()
-            .map(|combo| Hand::new_from_str(&combo.to_string()).unwrap())
-            .collect();
-        match (action, me, opponent, hand_range) {
-            (Ok(action), Ok(me), Ok(opponent), hand_range) => Range {
+    fn to_range(&self, index: usize) -> Result<Range, Vec<RangeError>> {
+        let error = |field: &'static str, message: String| RangeError {
+            index,
+            name: self.name.clone(),
+            field,
+            message,
+        };
+        let table = match &self.table {
+            Some(table) => TableSize::from_str(table).map_err(|err| error("table", err)),
+            None => Ok(TableSize::default()),
+        };
+        let seated = |position: Position| match table {
+            Ok(table)
+                if !table.has_seat(position)
+                    && ![Position::IP, Position::OOP, Position::NONE].contains(&position) =>
+            {
+                Err(format!("no {} seat at {}", position, table))
+            }
+            _ => Ok(position),
+        };
+        let line = match &self.line {
+            Some(line) => PreflopLine::from_str(line)
+                .and_then(|line| {
+                    line.players()
+                        .into_iter()
+                        .try_for_each(|position| seated(position).map(|_| ()))?;
+                    let spot = line
+                        .spot()
+                        .ok_or_else(|| format!("no chart action for {}", line))?;
+                    Ok(Some((line, spot)))
+                })
+                .map_err(|err| error("line", err)),
+            None => Ok(None),
+        };
+        let spot = match &line {
+            Ok(Some((_, spot))) => Some(*spot),
+            _ => None,
+        };
+        let action =
+            spot_field(&self.action, spot.map(|spot| spot.2)).map_err(|err| error("action", err));
+        let me = spot_field(&self.me, spot.map(|spot| spot.0))
+            .and_then(seated)
+            .map_err(|err| error("me", err));
+        let opponent = spot_field(&self.opponent, spot.map(|spot| spot.1))
+            .and_then(seated)
+            .map_err(|err| error("opponent", err));
+        let profile = match &self.option {
+            Some(option) => Profile::from_str(option).map_err(|err| error("option", err)),
+            None => Ok(Profile::Standard),
+        };
+        let stack = self
+            .stack
+            .as_deref()
+            .map(StackDepth::from_str)
+            .transpose()
+            .map_err(|err| error("stack", err));
+        let hand_range = parse_hands(&self.hands).map_err(|err| error("hands", err));
+        match (
+            action, me, opponent, profile, table, line, stack, hand_range,
+        ) {
+            (
+                Ok(action),
+                Ok(me),
+                Ok(opponent),
+                Ok(profile),
+                Ok(table),
+                Ok(line),
+                Ok(stack),
+                Ok(hand_range),
+            ) => Ok(Range {
                 name: self.name.clone(),
-                action: action,
-                me: me,
-                opponent: opponent,
-                hand_range: hand_range,
-                hands: hands,
+                action,
+                me,
+                opponent,
+                profile,
+                table,
+                line: line
+                    .map(|(line, _)| line)
+                    .unwrap_or_else(|| PreflopLine::from_spot(me, opponent, action)),
+                stack,
+                hand_range,
+            }),
+            (action, me, opponent, profile, table, line, stack, hand_range) => Err(vec![
+                action.err(),
+                me.err(),
+                opponent.err(),
+                profile.err(),
+                table.err(),
+                line.err(),
+                stack.err(),
+                hand_range.err(),
+            ]
+            .into_iter()
+            .flatten()
+            .collect()),
+        }
+    }
+}
+
+/// Parses a field of a pattern that `line` may also give, in which case it can be left
+/// empty but must agree with the line otherwise.
+fn spot_field<T>(text: &str, from_line: Option<T>) -> Result<T, String>
+where
+    T: FromStr<Err = String> + PartialEq + fmt::Display,
+{
+    match (text.is_empty(), from_line) {
+        (true, Some(value)) => Ok(value),
+        (_, from_line) => {
+            let value = T::from_str(text)?;
+            match from_line {
+                Some(expected) if expected != value => {
+                    Err(format!("{} doesn't match the line ({})", value, expected))
+                }
+                _ => Ok(value),
+            }
+        }
+    }
+}
+
+/// Parses range notation where any token may carry a frequency, e.g. "JJ+,AKs,A5s:0.5".
+///
+/// The frequency becomes the combo weight (0-100) used by the equity calculators, so
+/// "A5s:0.5" is the same as rust_poker's "A5s@50". `HandRange::from_string` silently stops
+/// at the first token it can't read, so every token is checked up front and all the bad
+/// ones are reported.
+pub fn parse_hands(text: &str) -> Result<HandRange, String> {
+    let (tokens, invalid): (Vec<_>, Vec<_>) = text
+        .split(',')
+        .map(|token| parse_token(token.trim()).ok_or_else(|| token.trim().to_string()))
+        .partition(|token| token.is_ok());
+    if !invalid.is_empty() {
+        return Err(format!(
+            "can't parse {}",
+            invalid
+                .into_iter()
+                .filter_map(|token| token.err())
+                .map(|token| format!("\"{}\"", token))
+                .join(", ")
+        ));
+    }
+    Ok(HandRange::from_string(
+        tokens.into_iter().filter_map(|token| token.ok()).join(","),
+    ))
+}
+
+/// Converts one token to rust_poker notation, or `None` if it isn't a valid hand.
+fn parse_token(token: &str) -> Option<String> {
+    let (hand, weight) = match (token.split_once(':'), token.split_once('@')) {
+        (Some((hand, frequency)), None) => {
+            let frequency = frequency.parse::<f64>().ok()?;
+            if !(0.0..=1.0).contains(&frequency) {
+                return None;
+            }
+            (hand, Some((frequency * 100.0).round() as u8))
+        }
+        (None, Some((hand, weight))) => {
+            (hand, Some(weight.parse::<u8>().ok().filter(|w| *w <= 100)?))
+        }
+        (None, None) => (token, None),
+        (Some(_), Some(_)) => return None,
+    };
+    if !is_hand(hand) {
+        return None;
+    }
+    Some(match weight {
+        Some(weight) => format!("{}@{}", hand, weight),
+        None => hand.to_string(),
+    })
+}
+
+/// "AKs", "AKo+", "77+", "AK" or an explicit combo such as "AhKh".
+fn is_hand(hand: &str) -> bool {
+    let chars = hand.to_lowercase().chars().collect::<Vec<_>>();
+    let rank = |c: char| char_to_rank(c) != u8::MAX;
+    let suit = |c: char| char_to_suit(c) != u8::MAX;
+    match chars[..] {
+        [r1, s1, r2, s2] if suit(s1) && suit(s2) => rank(r1) && rank(r2) && (r1, s1) != (r2, s2),
+        [r1, r2] => rank(r1) && rank(r2),
+        [r1, r2, '+'] => rank(r1) && rank(r2),
+        [r1, r2, 's'] | [r1, r2, 's', '+'] => rank(r1) && rank(r2) && r1 != r2,
+        [r1, r2, 'o'] | [r1, r2, 'o', '+'] => rank(r1) && rank(r2),
+        _ => false,
+    }
+}
+
+/// How often `combo` is in the range, from 0.0 to 1.0.
+pub fn frequency(hand_range: &HandRange, combo: &Combo) -> f64 {
+    hand_range
+        .hands
+        .iter()
+        .find(|hand| *hand == combo)
+        .map_or(0.0, |hand| f64::from(hand.2) / 100.0)
+}
+
+/// Reads the range chart.
+///
+/// `path` comes from `--ranges` (or `HEADSUP_RANGES`). Without it, the chart in the
+/// user config dir is used if present, and the built-in chart otherwise. Invalid and
+/// duplicate entries are reported on stderr and skipped; use `validate_ranges` to
+/// check a file.
+pub fn read_ranges(path: Option<&Path>) -> Result<Vec<Range>> {
+    let (ranges, errors) = load_patterns(&read_json_model(path)?);
+    errors.iter().for_each(|error| eprintln!("{}", error));
+    Ok(ranges)
+}
+
+/// Every problem in the range chart `read_ranges` would load.
+pub fn validate_ranges(path: Option<&Path>) -> Result<Vec<RangeError>> {
+    Ok(load_patterns(&read_json_model(path)?).1)
+}
+
+fn read_json_model(path: Option<&Path>) -> Result<JsonModel> {
+    match path.map(Path::to_path_buf).or_else(config_range_path) {
+        Some(path) => read_json_file(&path),
+        None => read_json_data(),
+    }
+}
+
+/// Converts the valid entries, keeping the first of any entries that share
+/// (table, line with sizes, stack, option).
+fn load_patterns(json_model: &JsonModel) -> (Vec<Range>, Vec<RangeError>) {
+    let mut ranges: Vec<(usize, Range)> = vec![];
+    let mut errors = vec![];
+    for (index, pattern) in json_model.patterns.iter().enumerate() {
+        match pattern.to_range(index) {
+            Ok(range) => match ranges
+                .iter()
+                .find(|(_, other)| other.same_spot(&range) && other.profile == range.profile)
+            {
+                Some((first, other)) => errors.push(RangeError {
+                    index,
+                    name: pattern.name.clone(),
+                    field: "key",
+                    message: format!("duplicate of #{} {}", first, other.name),
+                }),
+                None => ranges.push((index, range)),
             },
-            (_, _, _, _) => panic!("invalid pattern"),
+            Err(mut pattern_errors) => errors.append(&mut pattern_errors),
         }
     }
+    (ranges.into_iter().map(|(_, range)| range).collect(), errors)
 }
 
-pub fn read_ranges() -> Vec<Range> {
-    // let json_model = read_json_file();
-    let json_model = read_json_data();
-    json_model
-        .patterns
-        .into_iter()
-        .map(|pattern| pattern.to_range())
+/// Finds the chart for a spot, preferring `profile` and falling back to the standard chart
+/// when the profile doesn't define that spot.
+pub fn find_range<'a>(
+    ranges: &'a [Range],
+    me: Position,
+    opponent: Position,
+    action: Action,
+    profile: &Profile,
+    table: TableSize,
+) -> Option<&'a Range> {
+    find_line_range(
+        ranges,
+        &PreflopLine::from_spot(me, opponent, action),
+        profile,
+        table,
+        None,
+    )
+}
+
+/// Finds the chart for the last player of `line` with an effective `stack` in bb, trying
+/// `PreflopLine::chart_keys` in order.
+///
+/// For each key, `profile`'s own chart beats the standard one, then a chart for the stack
+/// depth beats one for any depth, then charts sizing more of the raises `line` sizes win.
+/// Without `stack`, charts for any depth are preferred.
+pub fn find_line_range<'a>(
+    ranges: &'a [Range],
+    line: &PreflopLine,
+    profile: &Profile,
+    table: TableSize,
+    stack: Option<f64>,
+) -> Option<&'a Range> {
+    line.chart_keys(table).iter().find_map(|key| {
+        ranges
+            .iter()
+            .filter(|range| range.table == table && range.line.fits(key))
+            .filter(|range| &range.profile == profile || range.profile.is_standard())
+            .filter(|range| match (range.stack, stack) {
+                (Some(depth), Some(stack)) => depth.contains(stack),
+                _ => true,
+            })
+            .rev()
+            .max_by_key(|range| {
+                (
+                    &range.profile == profile,
+                    range.stack.is_some() == stack.is_some(),
+                    range.line.matching_sizes(key),
+                )
+            })
+    })
+}
+
+/// The charts a player with `profile` uses at `table`: its own entries plus the standard
+/// entries for spots the profile doesn't cover.
+pub fn select_profile<'a>(
+    ranges: &'a [Range],
+    profile: &Profile,
+    table: TableSize,
+) -> Vec<&'a Range> {
+    ranges
+        .iter()
+        .filter(|range| range.table == table)
+        .filter(|range| {
+            &range.profile == profile
+                || (range.profile.is_standard()
+                    && !ranges
+                        .iter()
+                        .any(|other| &other.profile == profile && other.same_spot(range)))
+        })
         .collect()
 }
 
-fn read_json_file() -> JsonModel {
-    let file = File::open("range.json").unwrap();
+/// `$XDG_CONFIG_HOME/headsup-analyzer/range.json` (or `~/.config/...`), if it exists.
+fn config_range_path() -> Option<PathBuf> {
+    env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .map(|dir| dir.join("headsup-analyzer").join("range.json"))
+        .filter(|path| path.is_file())
+}
+
+fn read_json_file(path: &Path) -> Result<JsonModel> {
+    let file = File::open(path).with_context(|| format!("can't open {}", path.display()))?;
     let reader = BufReader::new(file);
+    serde_json::from_reader(reader).with_context(|| format!("can't read {}", path.display()))
+}
+
+fn read_json_data() -> Result<JsonModel> {
+    serde_json::from_str(BUILTIN_RANGES).context("can't read built-in range chart")
+}
 
-    // Read the JSON contents of the file as an instance of `User`.
-    let u = serde_json::from_reader(reader);
+/// Starting hands from strongest to weakest by equity against a random hand.
+const HAND_RANKING: &str = "AA,KK,QQ,JJ,TT,99,88,AKs,77,AQs,AJs,AKo,ATs,AQo,AJo,KQs,66,A9s,ATo,\
+KJs,A8s,KTs,KQo,A7s,A9o,KJo,55,QJs,K9s,A5s,A6s,A8o,KTo,QTs,A4s,A7o,K8s,A3s,QJo,K9o,A5o,A6o,Q9s,\
+K7s,JTs,A2s,QTo,44,A4o,K6s,K8o,Q8s,A3o,K5s,J9s,Q9o,JTo,K7o,A2o,K4s,Q7s,K6o,K3s,T9s,J8s,33,Q6s,\
+Q8o,K5o,J9o,K2s,Q5s,T8s,K4o,J7s,Q4s,Q7o,T9o,J8o,K3o,Q6o,Q3s,98s,T7s,J6s,K2o,22,Q2s,Q5o,J5s,T8o,\
+J7o,Q4o,97s,J4s,T6s,J3s,Q3o,98o,87s,T7o,J6o,96s,J2s,Q2o,T5s,J5o,T4s,97o,86s,J4o,T6o,95s,T3s,76s,\
+J3o,87o,T2s,85s,96o,J2o,T5o,94s,75s,T4o,93s,86o,65s,84s,95o,T3o,92s,76o,74s,T2o,54s,85o,64s,83s,\
+94o,75o,82s,73s,93o,65o,53s,63s,84o,92o,43s,74o,72s,54o,64o,52s,62s,83o,42s,82o,73o,53o,63o,32s,\
+43o,72o,52o,62o,42o,32o";
 
-    // Return the `User`.
-    u.ok().unwrap()
+fn hand_range_of(mut hands: Vec<Combo>) -> HandRange {
+    hands.sort();
+    hands.dedup();
+    HandRange {
+        hands,
+        char_vec: vec![],
+    }
 }
 
-fn read_json_data() -> JsonModel {
-    let data = r#"
-{
-  "patterns": [
-    {
-      "name": "UTGオープン",
-      "action": "open",
-      "me": "utg",
-      "opponent": "none",
-      "hands": "ATo+,A8s+,A4s,A5s,77+,KJo+,K9s+,QTs+,JTs"
-    },
-    {
-      "name": "MPオープン",
-      "action": "open",
-      "me": "mp",
-      "opponent": "none",
-      "hands": "ATo+,A2s+,66+,KJo+,K8s+,QJo+,Q9s+,J9s+,T9s+"
-    },
-    {
-      "name": "COオープン",
-      "action": "open",
-      "me": "co",
-      "opponent": "none",
-      "hands": "A9o+,A2s+,44+,KTo+,K6s+,QTo+,Q9s+,J9s+,JTo,T8s+,98s,87s,76s,65s"
-    },
-    {
-      "name": "BTNオープン",
-      "action": "open",
-      "me": "btn",
-      "opponent": "none",
-      "hands": "22+,A2s+,A4o+,K2s+,K9o+,Q6s+,QTo+,J7s+,J9o+,T7s+,T9o+,97s+,86s+,75s+,64s+,54s+"
-    },
-    {
-      "name": "SBオープン",
-      "action": "open",
-      "me": "sb",
-      "opponent": "none",
-      "hands": "22+,A2s+,A3o+,K2s+,K8o+,Q2s+,Q9o+,J5s+,J9o+,T6s+,T8o+,96s+,98o+,85s+,74s+,64s+,53s+,43s+"
-    },
-    {
-      "name": "MPコール vs UTG",
-      "action": "call",
-      "me": "mp",
-      "opponent": "utg",
-      "hands": "JJ,TT,99,AQs,AJs,KQs"
-    },
-    {
-      "name": "COコール vs UTG(EP)",
-      "action": "call",
-      "me": "co",
-      "opponent": "utg",
-      "hands": "JJ,TT,99,AQs,AJs,KQs"
-    },
-    {
-      "name": "COコール vs MP(EP)",
-      "action": "call",
-      "me": "co",
-      "opponent": "mp",
-      "hands": "JJ,99,AQs,AJs,KQs"
-    },
-    {
-      "name": "BTNコール vs UTG(EP)",
-      "action": "call",
-      "me": "btn",
-      "opponent": "utg",
-      "hands": "TT,99,88,AQs,AJs,ATs,AQo,AJo,KQs,KJs,KQo,QJs,JTs"
-    },
-    {
-      "name": "BTNコール vs MP(EP)",
-      "action": "call",
-      "me": "btn",
-      "opponent": "mp",
-      "hands": "TT,99,88,AQs,AJs,ATs,AQo,AJo,KQs,KJs,KQo,QJs,JTs"
-    },
-    {
-      "name": "BTNコール vs CO",
-      "action": "call",
-      "me": "btn",
-      "opponent": "co",
-      "hands": "99,88,77,AJs,ATs,AQo,QJs,JTs"
-    },
-    {
-      "name": "SBコール vs UTG(EP)",
-      "action": "call",
-      "me": "sb",
-      "opponent": "utg",
-      "hands": "99,88,77,KJs"
-    },
-    {
-      "name": "SBコール vs MP(EP)",
-      "action": "call",
-      "me": "sb",
-      "opponent": "mp",
-      "hands": "99,88,77,KJs"
-    },
-    {
-      "name": "BBコール vs UTG(EP)",
-      "action": "call",
-      "me": "bb",
-      "opponent": "utg",
-      "hands": "JJ,TT,99,88,77,66,55,44,33,22,AQs,AJs,ATs,A9s,A8s,A7s,A6s,A5s,A4s,A3s,A2s,AQo,AJo,ATo,K9s+,KQo,Q9s+,J9s+,T8s+,97s+,86s+,75s+,64s+,54s"
-    },
-    {
-      "name": "BBコール vs MP(EP)",
-      "action": "call",
-      "me": "bb",
-      "opponent": "mp",
-      "hands": "JJ,TT,99,88,77,66,55,44,33,22,AQs,AJs,ATs,A9s,A8s,A7s,A6s,A5s,A4s,A3s,A2s,AQo,AJo,ATo,K9s+,KQo,Q9s+,J9s+,T8s+,97s+,86s+,75s+,64s+,54s"
-    },
-    {
-      "name": "BBコール vs CO",
-      "action": "call",
-      "me": "bb",
-      "opponent": "co",
-      "hands": "TT,99,88,77,66,55,44,33,22,AJs,ATs,A9s,A8s,A7s,A6s,A3s,A2s,AQo,AJo,ATo,K7s+,KJo+,Q8s+,QJo,J8s+,T8s+,97s+,86s+,75s+,64s+,54s"
-    },
-    {
-      "name": "BBコール vs BTN",
-      "action": "call",
-      "me": "bb",
-      "opponent": "btn",
-      "hands": "77,66,55,44,33,22,A9s,A8s,A7s,A6s,AQo,AJo,ATo,A9o,K5s+,KT+o,Q7s+,QTo+,J8s+,JTo,T7s+,97s+,86s+,75s+,64s+,54s,43s"
-    },
-    {
-      "name": "BBコール vs SB",
-      "action": "call",
-      "me": "bb",
-      "opponent": "sb",
-      "hands": "77,66,55,44,33,22,A9s,A8s,A7s,A6s,A5s,A4s,A3s,A2s,AJo,ATo,K9s,K8s,K7s,K6s,K5s,K4s,K3s,K2s,K7o+,QTs,Q9s,Q8s,Q7s,Q6s,Q5s,Q4s,Q3s,Q2s,Q8o+,J9s,J8s,J7s,J6s,J5s,J4s,J3s,J8o+,T8s,T7s,T6s,T5s,T4s,T8o+,94s+,97o+,84s+,87o,74s+,63s+,52s+,42s+,32s"
-    },
-    {
-      "name": "MP3bet vs UTG",
-      "action": "3bet",
-      "me": "mp",
-      "opponent": "utg",
-      "hands": "AA,KK,QQ,ATs+,A5s,A4s,AQo+,KQs,KJs"
-    },
-    {
-      "name": "CO3bet vs UTG(EP)",
-      "action": "3bet",
-      "me": "co",
-      "opponent": "utg",
-      "hands": "AA,KK,QQ,ATs+,A5s,A4s,AQo+,KJs,65s,54s"
-    },
-    {
-      "name": "CO3bet vs MP(EP)",
-      "action": "3bet",
-      "me": "co",
-      "opponent": "mp",
-      "hands": "AA,KK,QQ,ATs+,A5s,A4s,AQo+,KJs,65s,54s"
-    },
-    {
-      "name": "BTN3bet vs UTG(EP)",
-      "action": "3bet",
-      "me": "btn",
-      "opponent": "utg",
-      "hands": "JJ+,AKs,A5s,A4s,AKo,AJo,KTs,KQo,76s,65s,54s"
-    },
-    {
-      "name": "BTN3bet vs MP(EP)",
-      "action": "3bet",
-      "me": "btn",
-      "opponent": "mp",
-      "hands": "JJ+,AKs,A5s,A4s,AKo,AJo,KTs,KQo,76s,65s,54s"
-    },
-    {
-      "name": "BTN3bet vs CO",
-      "action": "3bet",
-      "me": "btn",
-      "opponent": "co",
-      "hands": "TT+,AQs+,A9s,A8s,A5s,A4s,A3s,A2s,AKo,AJo,KTs,KQo,76s,65s,54s"
-    },
-    {
-      "name": "SB3bet vs UTG(EP)",
-      "action": "3bet",
-      "me": "sb",
-      "opponent": "utg",
-      "hands": "TT+,ATs+,A5s,A4s,AQo+,KQs"
-    },
-    {
-      "name": "SB3bet vs MP(EP)",
-      "action": "3bet",
-      "me": "sb",
-      "opponent": "mp",
-      "hands": "TT+,ATs+,A5s,A4s,AQo+,KQs"
-    },
-    {
-      "name": "SB3bet vs CO",
-      "action": "3bet",
-      "me": "sb",
-      "opponent": "co",
-      "hands": "66+,A8s+,A5s,A4s,A3s,AJo+,KTs+,KQo"
-    },
-    {
-      "name": "SB3bet vs BTN",
-      "action": "3bet",
-      "me": "sb",
-      "opponent": "btn",
-      "hands": "55+,A2s+,ATo+,K9s+,KJo+,QTs+,QJo+,J9s+,T9s+"
-    },
-    {
-      "name": "BB3bet vs UTG(EP)",
-      "action": "3bet",
-      "me": "bb",
-      "opponent": "utg",
-      "hands": "QQ+,AKs,ATs,A5s,A4s,AKo,ATo,K9s"
-    },
-    {
-      "name": "BB3bet vs MP(EP)",
-      "action": "3bet",
-      "me": "bb",
-      "opponent": "mp",
-      "hands": "QQ+,AKs,ATs,A5s,A4s,AKo,ATo,K9s"
-    },
-    {
-      "name": "BB3bet vs CO",
-      "action": "3bet",
-      "me": "bb",
-      "opponent": "co",
-      "hands": "JJ+,AQs+,A5s,A4s,AKo,A5o,K6s,K5s,K4s,KTo,Q7s,Q6s"
-    },
-    {
-      "name": "BB3bet vs BTN",
-      "action": "3bet",
-      "me": "bb",
-      "opponent": "btn",
-      "hands": "88+,ATs+,A5s,A4s,AQo+,A8o,A5o,KJs+,K4s,K3s,K2s,K9o,QJs+,JTs+,J7s,T9o"
-    },
-    {
-      "name": "BB3bet vs SB",
-      "action": "3bet",
-      "me": "bb",
-      "opponent": "sb",
-      "hands": "88+,ATs+,AQo+,A5o,A4o,KTs+,QJs,JTs,J2s,T9s,T3s,T2s,93s,83s,73s,72s,62s"
-    },
-    {
-      "name": "IP3betコール",
-      "action": "3betcall",
-      "me": "ip",
-      "opponent": "oop",
-      "hands": "QQ,JJ,TT,99,88,77,66,55,A9s+,A5s,A4s,AQo+,KTs+,QTs+,JTs,T9s,76s,65s,54s"
-    },
-    {
-      "name": "IP3betコール(nit)",
-      "action": "3betcall",
-      "me": "ip",
-      "opponent": "oop",
-      "option": "nit",
-      "hands": "KK,QQ,JJ,TT,99,88,77,66,AKo,ATs+,KQs,QJs,JTs"
-    },
-    {
-      "name": "OOP3betコール",
-      "action": "3betcall",
-      "me": "oop",
-      "opponent": "ip",
-      "hands": "QQ,JJ,TT,99,88,77,66,AQs,ATs,AKo,KJs,QJs,JTs"
-    },
-    {
-      "name": "OOP3betコール(nit)",
-      "action": "3betcall",
-      "me": "oop",
-      "opponent": "ip",
-      "option": "nit",
-      "hands": "QQ,JJ,AKo"
-    },
-    {
-      "name": "IP4bet",
-      "action": "4bet",
-      "me": "ip",
-      "opponent": "oop",
-      "hands": "KK+,A7s,A6s,K9s"
-    },
-    {
-      "name": "IP4bet(nit)",
-      "action": "4bet",
-      "me": "ip",
-      "opponent": "oop",
-      "option": "nit",
-      "hands": "AA,A5s"
-    },
-    {
-      "name": "OOP4bet",
-      "action": "4bet",
-      "me": "oop",
-      "opponent": "ip",
-      "option": "nit",
-      "hands": "KK+,AKs,AJs,KQs"
-    },
-    {
-      "name": "OOP4bet(nit)",
-      "action": "4bet",
-      "me": "oop",
-      "opponent": "ip",
-      "option": "nit",
-      "hands": "KK+,AQs+"
-    },
-    {
-      "name": "IP4betコール",
-      "action": "4betcall",
-      "me": "ip",
-      "opponent": "oop",
-      "hands": "KK+"
-    },
-    {
-      "name": "IP4betコール(nit)",
-      "action": "4betcall",
-      "me": "ip",
-      "opponent": "oop",
-      "option": "nit",
-      "hands": "AA"
-    },
-    {
-      "name": "OOP4betコール",
-      "action": "4betcall",
-      "me": "oop",
-      "opponent": "ip",
-      "hands": "KK+,AKs"
-    },
-    {
-      "name": "OOP4betコール(nit)",
-      "action": "4betcall",
-      "me": "oop",
-      "opponent": "ip",
-      "option": "nit",
-      "hands": "KK+,AKs"
-    },
+/// Combos in either range.
+pub fn union(a: &HandRange, b: &HandRange) -> HandRange {
+    hand_range_of(a.hands.iter().chain(b.hands.iter()).copied().collect())
+}
+
+/// Combos in both ranges.
+pub fn intersection(a: &HandRange, b: &HandRange) -> HandRange {
+    hand_range_of(
+        a.hands
+            .iter()
+            .filter(|combo| b.hands.contains(combo))
+            .copied()
+            .collect(),
+    )
+}
+
+/// Combos in `a` but not in `b`, e.g. a flat-call range as "open minus 3bet".
+pub fn difference(a: &HandRange, b: &HandRange) -> HandRange {
+    hand_range_of(
+        a.hands
+            .iter()
+            .filter(|combo| !b.hands.contains(combo))
+            .copied()
+            .collect(),
+    )
+}
+
+/// Every combo not in the range.
+pub fn complement(hand_range: &HandRange) -> HandRange {
+    difference(&HandRange::from_string("random".to_string()), hand_range)
+}
+
+pub fn overlaps(a: &HandRange, b: &HandRange) -> bool {
+    a.hands.iter().any(|combo| b.hands.contains(combo))
+}
+
+pub fn combo_count(hand_range: &HandRange) -> usize {
+    hand_range.hands.len()
+}
+
+/// Combos counted by frequency, so two combos at 50% count as one.
+pub fn weighted_combo_count(hand_range: &HandRange) -> f64 {
+    hand_range
+        .hands
+        .iter()
+        .map(|combo| f64::from(combo.2) / 100.0)
+        .sum()
+}
+
+/// The strongest `percent`% of all combos, taking whole hand classes from `HAND_RANKING`.
+pub fn top_percent(percent: f64) -> HandRange {
+    let target = TOTAL_COMBOS as f64 * percent / 100.0;
+    let mut hands: Vec<Combo> = vec![];
+    for class in HAND_RANKING.split(',') {
+        if hands.len() as f64 >= target {
+            break;
+        }
+        hands.append(&mut HandRange::from_string(class.to_string()).hands);
+    }
+    hand_range_of(hands)
+}
+
+/// Compresses a range back into notation such as "77+,ATs+,KQo,A5s:0.5,AhKh".
+///
+/// Only `+` runs are produced since `HandRange::from_string` doesn't read "99-77".
+/// Classes that are only partly in the range, or mix frequencies, are listed combo by combo.
+pub fn to_notation(hand_range: &HandRange) -> String {
+    let mut full: BTreeMap<HandClass, u8> = BTreeMap::new();
+    let mut partial: Vec<Combo> = vec![];
+    for (class, combos) in &hand_range
+        .hands
+        .iter()
+        .sorted_by_key(|combo| HandClass::from_combo(combo))
+        .group_by(|combo| HandClass::from_combo(combo))
     {
-      "name": "リンパー",
-      "action": "limp",
-      "me": "none",
-      "opponent": "none",
-      "hands": "22+,A2s+,KJ+,QJ+,T9s,98s,87s,76s,65s,54s"
+        let combos = combos.copied().collect::<Vec<_>>();
+        let weight = combos[0].2;
+        if combos.len() == class.combos() && combos.iter().all(|combo| combo.2 == weight) {
+            full.insert(class, weight);
+        } else {
+            partial.extend(combos);
+        }
     }
-  ]
+
+    let pairs = (0..RANK_COUNT)
+        .rev()
+        .map(|rank| HandClass {
+            high: rank,
+            low: rank,
+            suited: false,
+        })
+        .collect::<Vec<_>>();
+    let mut tokens = compress(&pairs, &full);
+    for high in (1..RANK_COUNT).rev() {
+        for &suited in &[true, false] {
+            let kickers = (0..high)
+                .rev()
+                .map(|low| HandClass { high, low, suited })
+                .collect::<Vec<_>>();
+            tokens.append(&mut compress(&kickers, &full));
+        }
+    }
+    tokens.extend(
+        partial
+            .iter()
+            .map(|combo| with_frequency(combo.to_string(), combo.2)),
+    );
+    tokens.join(",")
 }
-    "#;
-    serde_json::from_str(data).ok().unwrap()
+
+/// Lists the classes of `classes` (strongest first) that are in `full`, folding a leading
+/// run of equal weight into "77+" or "ATs+".
+fn compress(classes: &[HandClass], full: &BTreeMap<HandClass, u8>) -> Vec<String> {
+    let mut tokens = vec![];
+    let mut rest = classes;
+    if let Some(&weight) = classes.first().and_then(|class| full.get(class)) {
+        let run = classes
+            .iter()
+            .take_while(|class| full.get(class) == Some(&weight))
+            .count();
+        if run > 1 {
+            tokens.push(with_frequency(format!("{}+", classes[run - 1]), weight));
+            rest = &classes[run..];
+        }
+    }
+    tokens.extend(rest.iter().filter_map(|class| {
+        full.get(class)
+            .map(|&weight| with_frequency(class.to_string(), weight))
+    }));
+    tokens
+}
+
+fn with_frequency(token: String, weight: u8) -> String {
+    if weight == 100 {
+        token
+    } else {
+        format!("{}:{}", token, f64::from(weight) / 100.0)
+    }
 }
 
 #[cfg(test)]
@@ -447,16 +724,96 @@ mod tests {
     use super::*;
     #[test]
     fn test_read_json_file() {
-        read_json_file();
+        read_json_file(Path::new("range.json")).unwrap();
     }
 
     #[test]
     fn test_read_ranges() {
-        let ranges = read_ranges();
+        let ranges = read_ranges(Some(Path::new("range.json"))).unwrap();
         println!("{:#?}", ranges[0].hand_range);
         assert_eq!(ranges.len(), 46);
     }
 
+    #[test]
+    fn test_read_json_data() {
+        assert_eq!(read_json_data().unwrap().patterns.len(), 46);
+    }
+
+    #[test]
+    fn test_read_ranges_missing_file() {
+        assert!(read_ranges(Some(Path::new("no-such-range.json"))).is_err());
+    }
+
+    #[test]
+    fn test_find_range() {
+        let ranges = builtin_ranges();
+        let range = find_range(
+            &ranges,
+            Position::BTN,
+            Position::CO,
+            Action::ThreeBet,
+            &Profile::Standard,
+            TableSize::SixMax,
+        )
+        .unwrap();
+        assert_eq!(range.name(), "BTN3bet vs CO");
+    }
+
+    fn builtin_ranges() -> Vec<Range> {
+        let (ranges, errors) = load_patterns(&read_json_data().unwrap());
+        assert_eq!(errors, vec![]);
+        ranges
+    }
+
+    #[test]
+    fn test_find_range_by_profile() {
+        let ranges = builtin_ranges();
+        let find = |profile: &Profile| {
+            find_range(
+                &ranges,
+                Position::IP,
+                Position::OOP,
+                Action::ThreeBetCall,
+                profile,
+                TableSize::SixMax,
+            )
+            .unwrap()
+            .name()
+            .to_string()
+        };
+        assert_eq!(find(&Profile::Standard), "IP3betコール");
+        assert_eq!(find(&Profile::Nit), "IP3betコール(nit)");
+        // Profiles without their own chart for the spot fall back to standard.
+        assert_eq!(find(&Profile::Custom("lag".to_string())), "IP3betコール");
+    }
+
+    #[test]
+    fn test_select_profile() {
+        let ranges = builtin_ranges();
+        let nit = select_profile(&ranges, &Profile::Nit, TableSize::SixMax);
+        assert_eq!(nit.len(), 40);
+        assert!(nit.iter().any(|range| range.name() == "BTNオープン"));
+        assert!(nit.iter().all(|range| range.name() != "IP4bet"));
+    }
+
+    #[test]
+    fn test_to_string_with_profile() {
+        let pattern = Pattern {
+            name: "IP4bet(nit)".to_string(),
+            action: "4bet".to_string(),
+            me: "ip".to_string(),
+            opponent: "oop".to_string(),
+            option: Some("nit".to_string()),
+            line: None,
+            table: None,
+            stack: None,
+            hands: "AA,A5s".to_string(),
+        };
+        let range = pattern.to_range(0).unwrap();
+
+        assert_eq!(range.to_string(None), "- IP 4bet vs OOP (nit)".to_string());
+    }
+
     #[test]
     fn test_to_string() {
         let pattern = Pattern {
@@ -464,9 +821,13 @@ mod tests {
             action: "3bet".to_string(),
             me: "btn".to_string(),
             opponent: "mp".to_string(),
+            option: None,
+            line: None,
+            table: None,
+            stack: None,
             hands: "JJ+,AKs,A5s,A4s,AKo,AJo,KTs,KQo,76s,65s,54s".to_string(),
         };
-        let range = pattern.to_range();
+        let range = pattern.to_range(0).unwrap();
 
         assert_eq!(range.to_string(None), "- BTN 3bet vs MP".to_string());
     }
@@ -478,10 +839,264 @@ mod tests {
             action: "open".to_string(),
             me: "utg".to_string(),
             opponent: "none".to_string(),
+            option: None,
+            line: None,
+            table: None,
+            stack: None,
             hands: "ATo+,A8s+,A4s,A5s,77+,KJo+,K9s+,QTs+,JTs".to_string(),
         };
-        let range = pattern.to_range();
+        let range = pattern.to_range(0).unwrap();
 
         assert_eq!(range.to_string(None), "- UTG Open".to_string());
     }
+
+    fn pattern(name: &str, me: &str, hands: &str) -> Pattern {
+        Pattern {
+            name: name.to_string(),
+            action: "call".to_string(),
+            me: me.to_string(),
+            opponent: "btn".to_string(),
+            option: None,
+            line: None,
+            table: None,
+            stack: None,
+            hands: hands.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_invalid_pattern() {
+        let errors = pattern("BBコール vs BTN", "bb", "K5s+,KT+o,Q7s+,AKx")
+            .to_range(17)
+            .unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "hands");
+        assert_eq!(
+            errors[0].to_string(),
+            "#17 BBコール vs BTN: hands: can't parse \"KT+o\", \"AKx\""
+        );
+
+        let errors = pattern("?", "xx", "AA,KT+o").to_range(3).unwrap_err();
+        let fields = errors.iter().map(|error| error.field).collect::<Vec<_>>();
+        assert_eq!(fields, vec!["me", "hands"]);
+    }
+
+    #[test]
+    fn test_table_size() {
+        let mut nine_max = pattern("BBコール vs BTN (9max)", "bb", "AA");
+        nine_max.table = Some("9max".to_string());
+        let mut utg1 = pattern("UTG+1コール vs BTN", "utg+1", "KK");
+        utg1.table = Some("9max".to_string());
+        let json_model = JsonModel {
+            patterns: vec![pattern("BBコール vs BTN", "bb", "QQ"), nine_max, utg1],
+        };
+        let (ranges, errors) = load_patterns(&json_model);
+        assert_eq!(errors, vec![]);
+        let find = |table| {
+            find_range(
+                &ranges,
+                Position::BB,
+                Position::BTN,
+                Action::Call,
+                &Profile::Standard,
+                table,
+            )
+            .map(|range| range.name())
+        };
+        assert_eq!(find(TableSize::SixMax), Some("BBコール vs BTN"));
+        assert_eq!(find(TableSize::NineMax), Some("BBコール vs BTN (9max)"));
+        assert_eq!(find(TableSize::FourMax), None);
+
+        let errors = pattern("UTG+1コール vs BTN", "utg+1", "KK")
+            .to_range(0)
+            .unwrap_err();
+        assert_eq!(
+            errors[0].to_string(),
+            "#0 UTG+1コール vs BTN: me: no UTG+1 seat at 6max"
+        );
+    }
+
+    #[test]
+    fn test_line_pattern() {
+        let mut squeeze = pattern("BTNスクイーズ vs UTG+CO", "", "QQ+,AKs");
+        squeeze.action = "".to_string();
+        squeeze.opponent = "".to_string();
+        squeeze.line = Some("utg-open co-call btn-3bet".to_string());
+        let mut three_bet = pattern("BTN3bet vs UTG", "btn", "KK+");
+        three_bet.action = "3bet".to_string();
+        three_bet.opponent = "utg".to_string();
+        let (ranges, errors) = load_patterns(&JsonModel {
+            patterns: vec![squeeze, three_bet],
+        });
+        assert_eq!(errors, vec![]);
+        assert_eq!(
+            ranges[0].to_string(None),
+            "- BTN 3bet vs UTG [utg-open co-call btn-3bet]"
+        );
+        assert_eq!(ranges[1].to_string(None), "- BTN 3bet vs UTG");
+
+        let find = |text: &str| {
+            let line = PreflopLine::from_str(text).unwrap();
+            find_line_range(&ranges, &line, &Profile::Standard, TableSize::SixMax, None)
+                .map(|range| range.name())
+        };
+        assert_eq!(
+            find("utg-open co-call btn-3bet"),
+            Some("BTNスクイーズ vs UTG+CO")
+        );
+        assert_eq!(find("utg-open btn-3bet"), Some("BTN3bet vs UTG"));
+        assert_eq!(find("utg-open mp-call btn-3bet"), None);
+
+        let mut mismatch = pattern("?", "co", "AA");
+        mismatch.line = Some("utg-open co-call btn-3bet".to_string());
+        let errors = mismatch.to_range(0).unwrap_err();
+        let fields = errors.iter().map(|error| error.field).collect::<Vec<_>>();
+        assert_eq!(fields, vec!["action", "me", "opponent"]);
+        assert_eq!(errors[1].message, "CO doesn't match the line (BTN)");
+    }
+
+    #[test]
+    fn test_stack_depth() {
+        let depth = StackDepth::from_str("40-100").unwrap();
+        assert!(depth.contains(40.0) && depth.contains(99.5) && !depth.contains(100.0));
+        assert_eq!(depth.to_string(), "40-100bb");
+        let deep = StackDepth::from_str("100bb+").unwrap();
+        assert!(deep.contains(250.0) && !deep.contains(60.0));
+        assert!(StackDepth::from_str("100-40").is_err());
+        assert!(StackDepth::from_str("deep").is_err());
+    }
+
+    #[test]
+    fn test_find_by_stack_and_sizes() {
+        let chart = |name: &str, stack: Option<&str>, line: &str| {
+            let mut pattern = pattern(name, "", "AA");
+            pattern.action = "".to_string();
+            pattern.opponent = "".to_string();
+            pattern.line = Some(line.to_string());
+            pattern.stack = stack.map(str::to_string);
+            pattern
+        };
+        let (ranges, errors) = load_patterns(&JsonModel {
+            patterns: vec![
+                chart("any", None, "co-open btn-3bet"),
+                chart("shallow", Some("0-60"), "co-open btn-3bet"),
+                chart("deep", Some("150+"), "co-open btn-3bet"),
+                chart("deep, 3x open", Some("150+"), "co-open-3 btn-3bet"),
+            ],
+        });
+        assert_eq!(errors, vec![]);
+        assert_eq!(
+            ranges[1].to_string(None),
+            "- BTN 3bet vs CO 0-60bb".to_string()
+        );
+        let find = |text: &str, stack| {
+            let line = PreflopLine::from_str(text).unwrap();
+            find_line_range(&ranges, &line, &Profile::Standard, TableSize::SixMax, stack)
+                .map(|range| range.name())
+        };
+        assert_eq!(find("co-open btn-3bet", Some(40.0)), Some("shallow"));
+        assert_eq!(find("co-open btn-3bet", Some(100.0)), Some("any"));
+        assert_eq!(find("co-open btn-3bet", None), Some("any"));
+        assert_eq!(find("co-open-2.5 btn-3bet", Some(200.0)), Some("deep"));
+        assert_eq!(
+            find("co-open-3 btn-3bet", Some(200.0)),
+            Some("deep, 3x open")
+        );
+        assert_eq!(find("co-open btn-3bet", Some(200.0)), Some("deep"));
+    }
+
+    #[test]
+    fn test_load_patterns_duplicates() {
+        let json_model = JsonModel {
+            patterns: vec![
+                pattern("BBコール vs BTN", "bb", "AA"),
+                pattern("bad", "bb", "KT+o"),
+                pattern("BBコール vs BTN (2)", "bb", "KK"),
+                pattern("SBコール vs BTN", "sb", "QQ"),
+            ],
+        };
+        let (ranges, errors) = load_patterns(&json_model);
+        assert_eq!(ranges.len(), 2);
+        assert_eq!(ranges[0].name(), "BBコール vs BTN");
+        assert_eq!(
+            errors.iter().map(|error| error.index).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        assert_eq!(errors[1].field, "key");
+        assert_eq!(errors[1].message, "duplicate of #0 BBコール vs BTN");
+    }
+
+    fn hands(text: &str) -> HandRange {
+        parse_hands(text).unwrap()
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let open = hands("77+,ATs+,KQs");
+        let three_bet = hands("QQ+,AKs,A5s");
+        assert_eq!(combo_count(&union(&open, &three_bet)), 48 + 16 + 4 + 4);
+        assert_eq!(combo_count(&intersection(&open, &three_bet)), 18 + 4);
+        assert_eq!(
+            to_notation(&difference(&open, &three_bet)),
+            "JJ,TT,99,88,77,AQs,AJs,ATs,KQs"
+        );
+        assert!(overlaps(&open, &three_bet));
+        assert!(!overlaps(&hands("AA"), &hands("KK")));
+        assert_eq!(combo_count(&complement(&open)), TOTAL_COMBOS - 68);
+    }
+
+    #[test]
+    fn test_hand_ranking() {
+        let classes = HAND_RANKING
+            .split(',')
+            .collect::<std::collections::BTreeSet<_>>();
+        assert_eq!(classes.len(), 169);
+        assert_eq!(combo_count(&hands(HAND_RANKING)), TOTAL_COMBOS);
+    }
+
+    #[test]
+    fn test_top_percent() {
+        assert_eq!(to_notation(&top_percent(2.0)), "TT+");
+        assert_eq!(to_notation(&top_percent(5.0)), "77+,AJs+,AKo");
+        assert_eq!(combo_count(&top_percent(100.0)), TOTAL_COMBOS);
+    }
+
+    #[test]
+    fn test_to_notation() {
+        assert_eq!(to_notation(&hands("77+,ATs+,AQo+")), "77+,ATs+,AQo+");
+        assert_eq!(
+            to_notation(&hands("99,22,KJs,K9s,AhKh")),
+            "99,22,KJs,K9s,AhKh"
+        );
+        assert_eq!(to_notation(&hands("AK")), "AKs,AKo");
+        assert_eq!(
+            to_notation(&hands("QQ+:0.5,JJ,A5s:0.25")),
+            "QQ+:0.5,JJ,A5s:0.25"
+        );
+        assert_eq!(to_notation(&hands("AsKs,AhKh:0.5")), "AsKs,AhKh:0.5");
+    }
+
+    #[test]
+    fn test_parse_hands() {
+        let hand_range = hands("JJ+, AKs, A5s:0.5");
+        assert_eq!(combo_count(&hand_range), 24 + 4 + 4);
+        assert_eq!(weighted_combo_count(&hand_range), 24.0 + 4.0 + 2.0);
+        assert_eq!(frequency(&hand_range, &hands("As5s").hands[0]), 0.5);
+        assert_eq!(frequency(&hand_range, &hands("As4s").hands[0]), 0.0);
+        assert!(parse_hands("A5s:1.5").is_err());
+        assert!(parse_hands("A5s:half").is_err());
+    }
+
+    #[test]
+    fn test_to_notation_round_trip() {
+        builtin_ranges().iter().for_each(|range| {
+            let notation = to_notation(range.hand_range());
+            assert_eq!(
+                hands(&notation).hands,
+                range.hand_range().hands,
+                "{}",
+                notation
+            );
+        });
+    }
 }
@@ -0,0 +1,238 @@
+use std::fmt;
+
+use itertools::Itertools;
+use rs_poker::core::{Card, Value};
+
+use crate::core::{
+    board::{Board, Street},
+    cards::Cards,
+    madehand::MadeHand,
+};
+
+/// How good the unpaired hole card is among the ranks still free to be a kicker.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, PartialOrd, Ord)]
+pub enum Kicker {
+    Weak,
+    Good,
+    Top,
+}
+
+impl fmt::Display for Kicker {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Kicker::Weak => write!(f, "weak kicker"),
+            Kicker::Good => write!(f, "good kicker"),
+            Kicker::Top => write!(f, "top kicker"),
+        }
+    }
+}
+
+/// Hero's made hand relative to the board, weakest first. Hands that the board makes on its
+/// own (a paired board, board trips, a river board that plays) count as high card for hero.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, PartialOrd, Ord)]
+pub enum RelativeHand {
+    HighCard,
+    /// Pocket pair below every board card.
+    Underpair,
+    BottomPair,
+    /// A pair with a board card between the top and the bottom one, or a pocket pair between them.
+    MiddlePair,
+    TopPair(Kicker),
+    Overpair,
+    /// One pair on the board and one made with hero's cards.
+    BoardPairedTwoPair,
+    /// Both pairs made with hero's cards.
+    TwoPair,
+    /// One hole card matching a paired board.
+    Trips,
+    /// Pocket pair matching a board card.
+    Set,
+    Straight,
+    Flush,
+    /// Hero holds the highest card of the suit not on the board.
+    NutFlush,
+    FullHouse,
+    Quads,
+    StraightFlush,
+}
+
+impl RelativeHand {
+    /// Classifies hero's `hole` cards on the `board` from the made hand of them together.
+    pub fn classify(hole: &Cards, board: &Board) -> Result<Self, &'static str> {
+        let rank = hole.union(board.cards())?.rank()?;
+        if board.street() == Street::River && board.cards().rank()? == rank {
+            return Ok(RelativeHand::HighCard);
+        }
+        let madehand = rank.made_hand();
+        let hole_values = hole
+            .cards()
+            .iter()
+            .map(|card| card.value)
+            .collect::<Vec<_>>();
+        let board_values = board.cards().values();
+        let is_pocket_pair = |value: Value| hole_values.iter().all(|v| *v == value);
+        let holds = |value: Value| hole_values.contains(&value);
+        let on_board = |value: Value| board_values.contains(&value);
+        let board_paired = |value: Value| count(board.cards().cards(), value) >= 2;
+
+        let relative = match madehand {
+            MadeHand::RoyalStraightFlush | MadeHand::StraightFlush(_) => {
+                RelativeHand::StraightFlush
+            }
+            MadeHand::FourCard(_, _) => RelativeHand::Quads,
+            MadeHand::FullHouse(_, _) => RelativeHand::FullHouse,
+            MadeHand::Flush(..) => {
+                if has_nut_flush(hole.cards(), board.cards().cards()) {
+                    RelativeHand::NutFlush
+                } else {
+                    RelativeHand::Flush
+                }
+            }
+            MadeHand::Straight(_) => RelativeHand::Straight,
+            MadeHand::ThreeCard(value, _, _) if is_pocket_pair(value) => RelativeHand::Set,
+            MadeHand::ThreeCard(value, _, _) if holds(value) => RelativeHand::Trips,
+            MadeHand::TwoPair(high, low, _) => match (board_paired(high), board_paired(low)) {
+                (false, false) => RelativeHand::TwoPair,
+                (true, true) => RelativeHand::HighCard,
+                _ => RelativeHand::BoardPairedTwoPair,
+            },
+            MadeHand::OnePair(value, _, _, _) if board_paired(value) => RelativeHand::HighCard,
+            MadeHand::OnePair(value, _, _, _) if is_pocket_pair(value) => {
+                match (board_values.first(), board_values.last()) {
+                    (_, Some(top)) if value > *top => RelativeHand::Overpair,
+                    (Some(bottom), _) if value < *bottom => RelativeHand::Underpair,
+                    _ => RelativeHand::MiddlePair,
+                }
+            }
+            MadeHand::OnePair(value, _, _, _) if on_board(value) => {
+                match (board_values.first(), board_values.last()) {
+                    (_, Some(top)) if value == *top => {
+                        let kicker = hole_values.iter().find(|v| **v != value).copied();
+                        RelativeHand::TopPair(kicker_strength(kicker, &board_values))
+                    }
+                    (Some(bottom), _) if value == *bottom => RelativeHand::BottomPair,
+                    _ => RelativeHand::MiddlePair,
+                }
+            }
+            _ => RelativeHand::HighCard,
+        };
+        Ok(relative)
+    }
+}
+
+impl fmt::Display for RelativeHand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RelativeHand::HighCard => write!(f, "high card"),
+            RelativeHand::Underpair => write!(f, "underpair"),
+            RelativeHand::BottomPair => write!(f, "bottom pair"),
+            RelativeHand::MiddlePair => write!(f, "middle pair"),
+            RelativeHand::TopPair(kicker) => write!(f, "top pair, {}", kicker),
+            RelativeHand::Overpair => write!(f, "overpair"),
+            RelativeHand::BoardPairedTwoPair => write!(f, "two pair (paired board)"),
+            RelativeHand::TwoPair => write!(f, "two pair"),
+            RelativeHand::Trips => write!(f, "trips"),
+            RelativeHand::Set => write!(f, "set"),
+            RelativeHand::Straight => write!(f, "straight"),
+            RelativeHand::Flush => write!(f, "flush"),
+            RelativeHand::NutFlush => write!(f, "nut flush"),
+            RelativeHand::FullHouse => write!(f, "full house"),
+            RelativeHand::Quads => write!(f, "quads"),
+            RelativeHand::StraightFlush => write!(f, "straight flush"),
+        }
+    }
+}
+
+fn count(cards: &[Card], value: Value) -> usize {
+    cards.iter().filter(|card| card.value == value).count()
+}
+
+/// Top for the best kicker left once the board's ranks are taken, good for the next two.
+fn kicker_strength(kicker: Option<Value>, board_values: &[Value]) -> Kicker {
+    let kicker = match kicker {
+        Some(kicker) => kicker,
+        None => return Kicker::Weak,
+    };
+    let better = Value::values()
+        .iter()
+        .filter(|value| **value > kicker && !board_values.contains(value))
+        .count();
+    match better {
+        0 => Kicker::Top,
+        1 | 2 => Kicker::Good,
+        _ => Kicker::Weak,
+    }
+}
+
+/// Whether a hole card is the highest card of the board's flush suit that isn't on the board.
+fn has_nut_flush(hole: &[Card], board: &[Card]) -> bool {
+    let suit = match board
+        .iter()
+        .map(|card| card.suit)
+        .counts()
+        .into_iter()
+        .find(|(_, n)| *n >= 3)
+    {
+        Some((suit, _)) => suit,
+        None => return false,
+    };
+    let nut = Value::values()
+        .iter()
+        .rev()
+        .find(|value| {
+            !board
+                .iter()
+                .any(|card| card.suit == suit && card.value == **value)
+        })
+        .copied();
+    hole.iter()
+        .any(|card| card.suit == suit && Some(card.value) == nut)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        let cases = [
+            ("AhKd", "Kc7s2d", RelativeHand::TopPair(Kicker::Top)),
+            ("KhJd", "Kc7s2d", RelativeHand::TopPair(Kicker::Good)),
+            ("Kh8d", "Kc7s2d", RelativeHand::TopPair(Kicker::Weak)),
+            ("QhQd", "Jc7s2d", RelativeHand::Overpair),
+            ("5h5d", "Jc7s6d", RelativeHand::Underpair),
+            ("9h9d", "Jc7s2d", RelativeHand::MiddlePair),
+            ("8h7h", "2c7s9d", RelativeHand::MiddlePair),
+            ("Ah2h", "2c7s9d", RelativeHand::BottomPair),
+            ("AhQd", "Kc7s2d", RelativeHand::HighCard),
+            ("AhQd", "KcKs2d", RelativeHand::HighCard),
+            ("7h7d", "Kc7s2d", RelativeHand::Set),
+            ("Ah7d", "7c7s2d", RelativeHand::Trips),
+            ("Kh7d", "Kc7s2d", RelativeHand::TwoPair),
+            ("Ah2h", "KcKs2d", RelativeHand::BoardPairedTwoPair),
+            ("Ah5h", "Kh7h2h", RelativeHand::NutFlush),
+            ("Qh5h", "Kh7h2h", RelativeHand::Flush),
+            ("Kh5h", "Ah7h2h", RelativeHand::NutFlush),
+            ("8h9d", "Tc7s6d", RelativeHand::Straight),
+            ("7h7d", "Kc7s2d2h", RelativeHand::FullHouse),
+            // The board plays: none of hero's cards are in the best five.
+            ("2c3d", "Kh7h2h5h9h", RelativeHand::HighCard),
+            ("AsKd", "9c8d7h6s5c", RelativeHand::HighCard),
+            ("Ts2d", "9c8d7h6s5c", RelativeHand::Straight),
+            ("AhKd", "7c7s7d2h2c", RelativeHand::HighCard),
+        ];
+        for (hole, board, expected) in cases.iter() {
+            let hole = Cards::from_str(hole).unwrap();
+            let board = Board::from_str(board).unwrap();
+            assert_eq!(
+                RelativeHand::classify(&hole, &board),
+                Ok(*expected),
+                "{} on {}",
+                hole,
+                board
+            );
+        }
+    }
+}
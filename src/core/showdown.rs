@@ -0,0 +1,189 @@
+use std::{cmp::Ordering, fmt};
+
+use itertools::Itertools;
+use rs_poker::core::{Card, Suit, Value};
+
+use crate::core::{
+    board::{Board, Street},
+    cards::Cards,
+    equity::Equity,
+    evaluator::{self, HandRank},
+    madehand::MadeHand,
+};
+
+/// Hero's result at showdown.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Outcome {
+    Win,
+    Lose,
+    Tie,
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Outcome::Win => write!(f, "win"),
+            Outcome::Lose => write!(f, "lose"),
+            Outcome::Tie => write!(f, "tie"),
+        }
+    }
+}
+
+/// A player's best 5 cards out of the hole cards and the board.
+#[derive(Debug, PartialEq, Clone)]
+pub struct BestHand {
+    pub rank: HandRank,
+    pub made_hand: MadeHand,
+    pub cards: Vec<Card>,
+}
+
+impl BestHand {
+    fn new(hole: &Cards, board: &Board) -> Result<Self, &'static str> {
+        let all = hole.union(board.cards())?.cards().to_vec();
+        let rank = evaluator::evaluate(&all)?;
+        let cards = all
+            .into_iter()
+            .combinations(5)
+            .find(|five| evaluator::evaluate(five) == Ok(rank))
+            .ok_or("No best hand")?;
+        Ok(BestHand {
+            rank,
+            made_hand: rank.made_hand(),
+            cards,
+        })
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Showdown {
+    pub outcome: Outcome,
+    pub hero: BestHand,
+    pub villain: BestHand,
+}
+
+impl Board {
+    /// Who wins with `hero` against `villain` on this board, from the flop on.
+    pub fn showdown(&self, hero: &Cards, villain: &Cards) -> Result<Showdown, &'static str> {
+        if self.street() == Street::Preflop {
+            return Err("A showdown needs a flop, turn or river");
+        }
+        if hero.len() != 2 || villain.len() != 2 {
+            return Err("Hole cards must be 2 cards");
+        }
+        hero.union(villain)?;
+        let hero = BestHand::new(hero, self)?;
+        let villain = BestHand::new(villain, self)?;
+        let outcome = match hero.rank.cmp(&villain.rank) {
+            Ordering::Greater => Outcome::Win,
+            Ordering::Less => Outcome::Lose,
+            Ordering::Equal => Outcome::Tie,
+        };
+        Ok(Showdown {
+            outcome,
+            hero,
+            villain,
+        })
+    }
+
+    /// Hero's equity against villain over every runout of this board, by showdown.
+    pub fn runout_equity(&self, hero: &Cards, villain: &Cards) -> Result<Equity, &'static str> {
+        let dead = hero.union(villain)?.union(self.cards())?;
+        let deck = Value::values()
+            .iter()
+            .cartesian_product(Suit::suits().iter())
+            .map(|(value, suit)| Card {
+                value: *value,
+                suit: *suit,
+            })
+            .filter(|card| !dead.contains(card))
+            .collect::<Vec<_>>();
+        let (mut win, mut tie, mut lose) = (0_u64, 0_u64, 0_u64);
+        for runout in deck.into_iter().combinations(self.cards_to_come()) {
+            let board = self.deal(&Cards::new(runout)?)?;
+            match board.showdown(hero, villain)?.outcome {
+                Outcome::Win => win += 1,
+                Outcome::Tie => tie += 1,
+                Outcome::Lose => lose += 1,
+            }
+        }
+        let count = (win + tie + lose) as f64;
+        Ok(Equity {
+            win: win as f64 / count,
+            tie: tie as f64 / count,
+            lose: lose as f64 / count,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use rust_poker::hand_range::{get_card_mask, HandRange};
+
+    use super::*;
+    use crate::core::equity;
+
+    fn showdown(hero: &str, villain: &str, board: &str) -> Showdown {
+        Board::from_str(board)
+            .unwrap()
+            .showdown(
+                &Cards::from_str(hero).unwrap(),
+                &Cards::from_str(villain).unwrap(),
+            )
+            .unwrap()
+    }
+
+    #[test]
+    fn test_showdown() {
+        let result = showdown("AhKd", "QsQc", "Kc7s2d9h3c");
+        assert_eq!(result.outcome, Outcome::Win);
+        assert_eq!(
+            result.hero.made_hand,
+            MadeHand::OnePair(Value::King, Value::Ace, Value::Nine, Value::Seven)
+        );
+        assert_eq!(
+            result
+                .hero
+                .cards
+                .iter()
+                .map(|c| c.to_string())
+                .collect::<Vec<_>>(),
+            vec!["Ah", "Kd", "Kc", "7s", "9h"]
+        );
+        assert_eq!(
+            showdown("QsQc", "AhKd", "Kc7s2d9h3c").outcome,
+            Outcome::Lose
+        );
+        // Both play the board's straight.
+        assert_eq!(showdown("2h2d", "3h3d", "9cTsJdQhKc").outcome, Outcome::Tie);
+        // Kicker decides between the same pair.
+        assert_eq!(showdown("AhKd", "KhQd", "Kc7s2d9h3c").outcome, Outcome::Win);
+        assert!(Board::from_str("Kc7s2d9h3c")
+            .unwrap()
+            .showdown(
+                &Cards::from_str("AhKd").unwrap(),
+                &Cards::from_str("AhQd").unwrap()
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn test_runout_equity_like_exact_equity() {
+        let board = Board::from_str("Kc7s2d").unwrap();
+        let ours = board
+            .runout_equity(
+                &Cards::from_str("AhKd").unwrap(),
+                &Cards::from_str("8h9h").unwrap(),
+            )
+            .unwrap();
+        let theirs = equity::exact_equity(
+            &HandRange::from_string("AhKd".to_string()),
+            &HandRange::from_string("8h9h".to_string()),
+            get_card_mask("Kc7s2d"),
+        )
+        .unwrap();
+        assert_eq!(format!("{:.6}", ours.win), format!("{:.6}", theirs.win));
+        assert_eq!(format!("{:.6}", ours.tie), format!("{:.6}", theirs.tie));
+    }
+}
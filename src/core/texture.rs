@@ -0,0 +1,410 @@
+use std::fmt;
+
+use itertools::Itertools;
+use rs_poker::core::{Card, Suit, Value};
+
+use crate::core::straight;
+
+/// Most cards of one suit on the board: one, two, or three and more.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Suitedness {
+    Rainbow,
+    TwoTone,
+    Monotone,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Pairing {
+    Unpaired,
+    Paired,
+    DoublePaired,
+    Trips,
+    FullHouse,
+    Quads,
+}
+
+/// Connected when a straight is possible, semi-connected when two board ranks could share one.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Connectedness {
+    Disconnected,
+    SemiConnected,
+    Connected,
+}
+
+/// Class of the highest board card.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum HighCard {
+    AceHigh,
+    /// Ten to king high.
+    Broadway,
+    /// Seven to nine high.
+    Middle,
+    Low,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Wetness {
+    Dry,
+    Medium,
+    Wet,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Texture {
+    pub suitedness: Suitedness,
+    pub pairing: Pairing,
+    pub connectedness: Connectedness,
+    pub high_card: HighCard,
+    /// How many draws the board allows; see `wetness`.
+    pub score: u8,
+    /// Highest card of every straight two hole cards can make, five for the wheel.
+    pub straights: Vec<Value>,
+    /// Suits with three or more cards on the board.
+    pub flushes: Vec<Suit>,
+}
+
+impl Texture {
+    pub fn new(cards: &[Card]) -> Self {
+        let straights = possible_straights(cards);
+        let flushes = possible_flushes(cards);
+        let max_suit = cards
+            .iter()
+            .map(|card| card.suit)
+            .counts()
+            .into_values()
+            .max();
+        let suitedness = match max_suit.unwrap_or(0) {
+            0 | 1 => Suitedness::Rainbow,
+            2 => Suitedness::TwoTone,
+            _ => Suitedness::Monotone,
+        };
+        let connectedness = if !straights.is_empty() {
+            Connectedness::Connected
+        } else if max_in_straight(cards) >= 2 {
+            Connectedness::SemiConnected
+        } else {
+            Connectedness::Disconnected
+        };
+        let high_card = match cards.iter().map(|card| card.value).max() {
+            Some(Value::Ace) => HighCard::AceHigh,
+            Some(value) if value >= Value::Ten => HighCard::Broadway,
+            Some(value) if value >= Value::Seven => HighCard::Middle,
+            _ => HighCard::Low,
+        };
+        let pairing = pairing(cards);
+
+        // Flush and straight draws still to come make a board wet; pairs take draws away.
+        let flush_score = match (suitedness, cards.len() < 5) {
+            (Suitedness::Monotone, _) => 3,
+            (Suitedness::TwoTone, true) => 2,
+            _ => 0,
+        };
+        let straight_score = match connectedness {
+            Connectedness::Connected => 1 + straights.len().min(3) as u8,
+            Connectedness::SemiConnected => 1,
+            Connectedness::Disconnected => 0,
+        };
+        let pair_penalty = if pairing == Pairing::Unpaired { 0 } else { 1 };
+        let score = (flush_score + straight_score).saturating_sub(pair_penalty);
+
+        Texture {
+            suitedness,
+            pairing,
+            connectedness,
+            high_card,
+            score,
+            straights,
+            flushes,
+        }
+    }
+
+    pub fn wetness(&self) -> Wetness {
+        match self.score {
+            0 | 1 => Wetness::Dry,
+            2 | 3 => Wetness::Medium,
+            _ => Wetness::Wet,
+        }
+    }
+}
+
+/// What a turn or river card changes about the board.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, PartialOrd, Ord)]
+pub enum Transition {
+    /// Higher than every card already on the board.
+    Overcard,
+    PairsBoard,
+    /// Second card of a suit on the turn.
+    FlushDraw,
+    /// Third card of a suit.
+    FlushCompletes,
+    /// Makes a straight possible that wasn't before.
+    StraightCompletes,
+    /// None of the above.
+    Blank,
+}
+
+impl Transition {
+    /// How `card` changes the `previous` board.
+    pub fn between(previous: &[Card], card: Card) -> Vec<Transition> {
+        let mut next = previous.to_vec();
+        next.push(card);
+        let suited = previous.iter().filter(|c| c.suit == card.suit).count();
+        let before = possible_straights(previous);
+
+        let mut transitions = vec![];
+        if previous.iter().all(|c| c.value < card.value) {
+            transitions.push(Transition::Overcard);
+        }
+        if previous.iter().any(|c| c.value == card.value) {
+            transitions.push(Transition::PairsBoard);
+        }
+        match suited {
+            1 if next.len() < 5 => transitions.push(Transition::FlushDraw),
+            2 => transitions.push(Transition::FlushCompletes),
+            _ => {}
+        }
+        if possible_straights(&next)
+            .iter()
+            .any(|straight| !before.contains(straight))
+        {
+            transitions.push(Transition::StraightCompletes);
+        }
+        if transitions.is_empty() {
+            transitions.push(Transition::Blank);
+        }
+        transitions
+    }
+
+    /// Every card left in the deck for the next street, counted by what it does to `board`.
+    pub fn next_cards(board: &[Card]) -> Vec<(Transition, usize)> {
+        Value::values()
+            .iter()
+            .flat_map(|&value| {
+                Suit::suits()
+                    .to_vec()
+                    .into_iter()
+                    .map(move |suit| Card { value, suit })
+            })
+            .filter(|card| !board.contains(card))
+            .flat_map(|card| Transition::between(board, card))
+            .counts()
+            .into_iter()
+            .sorted()
+            .collect()
+    }
+}
+
+fn pairing(cards: &[Card]) -> Pairing {
+    let counts = cards
+        .iter()
+        .map(|card| card.value)
+        .counts()
+        .into_values()
+        .sorted()
+        .rev()
+        .collect::<Vec<_>>();
+    match (counts.first(), counts.get(1)) {
+        (Some(4), _) => Pairing::Quads,
+        (Some(3), Some(2)) => Pairing::FullHouse,
+        (Some(3), _) => Pairing::Trips,
+        (Some(2), Some(2)) => Pairing::DoublePaired,
+        (Some(2), _) => Pairing::Paired,
+        _ => Pairing::Unpaired,
+    }
+}
+
+/// Most distinct board ranks inside one straight.
+fn max_in_straight(cards: &[Card]) -> usize {
+    straight::get_straight_patterns()
+        .iter()
+        .map(|pattern| {
+            pattern
+                .iter()
+                .filter(|value| cards.iter().any(|card| card.value == **value))
+                .count()
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+fn possible_straights(cards: &[Card]) -> Vec<Value> {
+    straight::get_straight_patterns()
+        .iter()
+        .filter(|pattern| {
+            pattern
+                .iter()
+                .filter(|value| cards.iter().any(|card| card.value == **value))
+                .count()
+                >= 3
+        })
+        .map(|pattern| pattern[4])
+        .collect()
+}
+
+fn possible_flushes(cards: &[Card]) -> Vec<Suit> {
+    cards
+        .iter()
+        .map(|card| card.suit)
+        .counts()
+        .into_iter()
+        .filter(|(_, count)| *count >= 3)
+        .map(|(suit, _)| suit)
+        .sorted()
+        .collect()
+}
+
+impl fmt::Display for Suitedness {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Suitedness::Rainbow => write!(f, "rainbow"),
+            Suitedness::TwoTone => write!(f, "two-tone"),
+            Suitedness::Monotone => write!(f, "monotone"),
+        }
+    }
+}
+
+impl fmt::Display for Pairing {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Pairing::Unpaired => write!(f, "unpaired"),
+            Pairing::Paired => write!(f, "paired"),
+            Pairing::DoublePaired => write!(f, "double paired"),
+            Pairing::Trips => write!(f, "trips"),
+            Pairing::FullHouse => write!(f, "full house"),
+            Pairing::Quads => write!(f, "quads"),
+        }
+    }
+}
+
+impl fmt::Display for Connectedness {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Connectedness::Disconnected => write!(f, "disconnected"),
+            Connectedness::SemiConnected => write!(f, "semi-connected"),
+            Connectedness::Connected => write!(f, "connected"),
+        }
+    }
+}
+
+impl fmt::Display for HighCard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HighCard::AceHigh => write!(f, "A-high"),
+            HighCard::Broadway => write!(f, "broadway"),
+            HighCard::Middle => write!(f, "middle"),
+            HighCard::Low => write!(f, "low"),
+        }
+    }
+}
+
+impl fmt::Display for Wetness {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Wetness::Dry => write!(f, "dry"),
+            Wetness::Medium => write!(f, "medium"),
+            Wetness::Wet => write!(f, "wet"),
+        }
+    }
+}
+
+impl fmt::Display for Texture {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}, {}, {}, {}, {} ({})",
+            self.suitedness,
+            self.pairing,
+            self.connectedness,
+            self.high_card,
+            self.wetness(),
+            self.score
+        )
+    }
+}
+
+impl fmt::Display for Transition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Transition::Overcard => write!(f, "overcard"),
+            Transition::PairsBoard => write!(f, "pairs the board"),
+            Transition::FlushDraw => write!(f, "flush draw"),
+            Transition::FlushCompletes => write!(f, "flush completes"),
+            Transition::StraightCompletes => write!(f, "straight completes"),
+            Transition::Blank => write!(f, "blank"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::core::cards::Cards;
+
+    fn texture(board: &str) -> Texture {
+        Texture::new(Cards::from_str(board).unwrap().cards())
+    }
+
+    #[test]
+    fn test_texture() {
+        let dry = texture("Kc7s2d");
+        assert_eq!(dry.suitedness, Suitedness::Rainbow);
+        assert_eq!(dry.pairing, Pairing::Unpaired);
+        assert_eq!(dry.connectedness, Connectedness::Disconnected);
+        assert_eq!(dry.high_card, HighCard::Broadway);
+        assert_eq!(dry.wetness(), Wetness::Dry);
+        assert!(dry.straights.is_empty());
+
+        let wet = texture("9h8h7c");
+        assert_eq!(wet.suitedness, Suitedness::TwoTone);
+        assert_eq!(wet.connectedness, Connectedness::Connected);
+        assert_eq!(wet.high_card, HighCard::Middle);
+        assert_eq!(wet.straights, vec![Value::Nine, Value::Ten, Value::Jack]);
+        assert_eq!(wet.wetness(), Wetness::Wet);
+
+        let monotone = texture("AhQh4h");
+        assert_eq!(monotone.suitedness, Suitedness::Monotone);
+        assert_eq!(monotone.flushes, vec![Suit::Heart]);
+        assert_eq!(monotone.high_card, HighCard::AceHigh);
+        assert_eq!(monotone.connectedness, Connectedness::SemiConnected);
+
+        assert_eq!(texture("5c5d2h").pairing, Pairing::Paired);
+        assert_eq!(texture("5c5d2h").high_card, HighCard::Low);
+        assert_eq!(texture("5c5d5h").pairing, Pairing::Trips);
+        assert_eq!(texture("5c5d2h2s").pairing, Pairing::DoublePaired);
+        assert_eq!(texture("5c5d2h2s5s").pairing, Pairing::FullHouse);
+        assert_eq!(texture("Ac2d3h").straights, vec![Value::Five]);
+    }
+
+    #[test]
+    fn test_transitions() {
+        let flop = Cards::from_str("Kh7h2d").unwrap().cards().to_vec();
+        let card = |s: &str| Cards::from_str(s).unwrap().cards()[0];
+        assert_eq!(
+            Transition::between(&flop, card("Ah")),
+            vec![Transition::Overcard, Transition::FlushCompletes]
+        );
+        assert_eq!(
+            Transition::between(&flop, card("7c")),
+            vec![Transition::PairsBoard]
+        );
+        assert_eq!(
+            Transition::between(&flop, card("3c")),
+            vec![Transition::Blank]
+        );
+        assert_eq!(
+            Transition::between(&flop, card("5d")),
+            vec![Transition::FlushDraw]
+        );
+        assert_eq!(
+            Transition::between(Cards::from_str("9h8c2d").unwrap().cards(), card("7s")),
+            vec![Transition::StraightCompletes]
+        );
+
+        let next = Transition::next_cards(&flop);
+        assert!(next.contains(&(Transition::FlushCompletes, 11)));
+        assert!(next.contains(&(Transition::PairsBoard, 9)));
+        assert!(next.contains(&(Transition::Overcard, 4)));
+    }
+}
@@ -1 +1,4 @@
+#[allow(clippy::module_inception)]
 pub mod duel;
+
+pub mod report;
@@ -1,10 +1,30 @@
+use crate::board::{board::print_texture, report::TextureReport};
 use crate::core::{
-    action::Action, board::Board, cards::Cards, hand_wrapper::HandWrapper, player::Player,
-    position::Position,
+    action::Action,
+    board::{Board, Street},
+    cards::Cards,
+    distribution::Distribution,
+    drawhand::Outs,
+    equity::{self, EXACT_RUNOUT_LIMIT, HAND_EXACT_RUNOUT_LIMIT},
+    hand_class::HandClass,
+    hand_wrapper::HandWrapper,
+    line::PreflopLine,
+    odds::{self, Verdict},
+    player::Player,
+    position::{Position, TableSize},
+    profile::Profile,
+    range::Range,
+    relative_hand::RelativeHand,
 };
-use rust_poker::hand_range::{get_card_mask, HandRange};
+use crate::duel::report::{
+    self, Breakdown, BreakdownReport, DistributionReport, DuelReport, EquityReport,
+    ImpliedOddsReport, OddsReport, OutputFormat, OutsReport, PlayerReport, SCHEMA_VERSION,
+};
+use rust_poker::hand_range::HandRange;
 
+use anyhow::bail;
 use clap::Clap;
+use std::{collections::BTreeSet, str::FromStr};
 
 #[derive(Clap)]
 pub struct Duel {
@@ -14,60 +34,665 @@ pub struct Duel {
     pub hand: HandWrapper,
     #[clap(name = "VILLAIN_POSITION")]
     pub villain_positon: Position,
+    /// Villain's last action, or the whole preflop line such as "co-open btn-3bet co-call"
     #[clap(name = "VILLAIN_ACTION")]
-    pub villain_action: Action,
+    pub villain_action: VillainAction,
+    /// Flop, turn or river; leave out for preflop
     #[clap(name = "BOARD")]
-    pub board: Board,
+    pub board: Option<Board>,
+    /// Range profile for both players (standard, nit or a custom option name)
+    #[clap(long, default_value = "standard")]
+    pub profile: Profile,
+    /// Range profile for hero, overriding --profile
+    #[clap(long)]
+    pub hero_profile: Option<Profile>,
+    /// Range profile for villain, overriding --profile
+    #[clap(long)]
+    pub villain_profile: Option<Profile>,
+    /// Effective stack in bb at the start of the hand
+    #[clap(long, default_value = "100")]
+    pub stack: f64,
+    /// Raise sizes in bb for the open, 3bet and 4bet, e.g. 2.5,9,22 (the line's own sizes win)
+    #[clap(long, use_delimiter = true)]
+    pub sizes: Vec<f64>,
+    /// Table size: 3max, 4max, 5max, 6max or 9max
+    #[clap(long, default_value = "6max")]
+    pub table: TableSize,
+    /// Enumerate every runout instead of sampling (automatic on the turn and river, not
+    /// available preflop)
+    #[clap(long)]
+    pub exact: bool,
+    /// Output format: text or json
+    #[clap(long, default_value = "text")]
+    pub output: OutputFormat,
+    /// List hero's equity against each villain combo or hand class (combo or class)
+    #[clap(long)]
+    pub breakdown: Option<Breakdown>,
+    /// Pot in bb before the bet hero faces (defaults to the pot after the preflop line)
+    #[clap(long)]
+    pub pot: Option<f64>,
+    /// Bet in bb hero faces, to get the break-even equity and a call/fold verdict
+    #[clap(long)]
+    pub bet: Option<f64>,
+    /// Another player in the pot as POSITION:ACTION, e.g. btn:call (repeatable)
+    #[clap(long = "villain", multiple_occurrences = true, number_of_values = 1)]
+    pub other_villains: Vec<VillainSpot>,
+}
+
+/// A player other than the main villain, with their last action.
+#[derive(Debug, Clone, Copy)]
+pub struct VillainSpot {
+    pub position: Position,
+    pub action: Action,
+}
+
+impl FromStr for VillainSpot {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split(':').collect::<Vec<_>>()[..] {
+            [position, action] => Ok(VillainSpot {
+                position: Position::from_str(position)?,
+                action: Action::from_str(action)?,
+            }),
+            _ => Err(format!("Invalid villain: {} (expected POSITION:ACTION)", s)),
+        }
+    }
+}
+
+/// Villain's last action in a heads-up pot, or the preflop line of any pot.
+#[derive(Debug, Clone)]
+pub enum VillainAction {
+    Action(Action),
+    Line(PreflopLine),
+}
+
+impl FromStr for VillainAction {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.contains('-') {
+            PreflopLine::from_str(s).map(Self::Line)
+        } else {
+            Action::from_str(s).map(Self::Action)
+        }
+    }
 }
 
 impl Duel {
-    pub fn analyse(&self) {
-        let (hero, villain) = self.create_hero_and_villain().unwrap();
+    pub fn analyse(&self, ranges: &[Range]) -> anyhow::Result<()> {
+        let line = self
+            .line()
+            .map_err(anyhow::Error::msg)?
+            .with_sizes(&self.sizes);
+        let (mut hero, mut villains) = self
+            .create_players(ranges, &line)
+            .map_err(anyhow::Error::msg)?;
+        let others = self
+            .other_villains
+            .iter()
+            .map(|spot| spot.position)
+            .collect::<Vec<_>>();
+        let line = line.with_callers(&others, self.table);
+        let pot = line.pot(self.stack);
+        let spr = self.spr(&line);
+
+        let board = self.board();
+        if self.exact && board.street() == Street::Preflop {
+            // Every 5-card runout for every pair of combos is far too many.
+            bail!("--exact needs a flop, turn or river");
+        }
+        let available_cards = self.create_available_cards().map_err(anyhow::Error::msg)?;
+        hero.remove_cards(board.cards().mask());
+        villains
+            .iter_mut()
+            .for_each(|villain| villain.remove_cards(available_cards.mask()));
 
-        let available_cards: Cards = self.create_available_cards().unwrap();
+        let street = board.street();
+        let texture = match street {
+            Street::Preflop => None,
+            _ => Some(TextureReport::new(&board)),
+        };
         let madehand = available_cards.analyze_madehand();
-        println!("{:?}", madehand);
+        let relative_hand = RelativeHand::classify(&self.hand.cards, &board);
+        // Nothing is left to draw to on the river.
+        let outs = match street {
+            Street::Flop | Street::Turn => self
+                .hand
+                .cards
+                .analyse_outs_on(board.cards())
+                .map_err(anyhow::Error::msg)?,
+            Street::Preflop | Street::River => Outs::default(),
+        };
+        let drawhands = outs.draws.iter().map(|(draw, _)| *draw).collect::<Vec<_>>();
 
-        let drawhands = available_cards.analyse_drawhand();
-        println!("{:?}", drawhands);
+        let villain_hand_ranges = villains
+            .iter()
+            .map(|villain| &villain.hand_range)
+            .collect::<Vec<_>>();
+        let runouts = equity::runout_count(street.board_len());
+        let exact = self.exact || runouts <= EXACT_RUNOUT_LIMIT;
+        let equity = analyse(&hero.hand_range, &villain_hand_ranges, &board, exact);
 
-        analyse(hero.hand_range, villain.hand_range, &self.board);
+        let hand_exact = self.exact || runouts <= HAND_EXACT_RUNOUT_LIMIT;
+        let hero_hand_range = HandRange::from_string(self.hand.cards.to_string());
+        let hand_equity = analyse(&hero_hand_range, &villain_hand_ranges, &board, hand_exact);
+        let breakdown = match (self.breakdown, &villains[..]) {
+            (Some(breakdown), [villain]) => {
+                self.breakdown(breakdown, &hero_hand_range, villain, hand_exact)
+            }
+            (Some(_), _) => {
+                eprintln!("breakdown is only available heads-up");
+                vec![]
+            }
+            (None, _) => vec![],
+        };
+
+        let players = match street {
+            Street::Preflop => vec![],
+            _ => std::iter::once(&hero).chain(villains.iter()).collect(),
+        };
+        let distributions = players
+            .into_iter()
+            .map(|player| {
+                (
+                    player.position,
+                    Distribution::new(&player.hand_range, &board),
+                )
+            })
+            .collect::<Vec<_>>();
+        let ranges = distributions
+            .iter()
+            .map(|(position, distribution)| {
+                DistributionReport::new(position.to_string(), distribution)
+            })
+            .collect::<Vec<_>>();
+        let range_advantage = report::advantage(&ranges, |range| range.value);
+        let nut_advantage = report::advantage(&ranges, |range| range.nuts);
+
+        let players = std::iter::once(&hero).chain(villains.iter());
+        let equities = equity
+            .as_ref()
+            .map_or(vec![], |(_, equities)| equities.clone());
+        let player_reports = players
+            .map(PlayerReport::from)
+            .enumerate()
+            .map(|(i, report)| PlayerReport {
+                equity: equities.get(i).copied(),
+                ..report
+            })
+            .collect::<Vec<_>>();
+        let equity = equity.map(|(equity, _)| equity);
+        let hand_equity = hand_equity.map(|(equity, _)| equity);
+        let odds = match (self.bet, &hand_equity) {
+            (Some(bet), Ok(hand_equity)) => Some(self.odds(
+                &line,
+                self.pot.unwrap_or(pot),
+                bet,
+                hand_equity.equity,
+                !drawhands.is_empty(),
+            )),
+            _ => None,
+        };
+
+        match self.output {
+            OutputFormat::Text => {
+                println!("Pot: {}bb / SPR: {:?}", pot, round(spr));
+                println!("Street: {}", street);
+                if let Some(texture) = &texture {
+                    print_texture(texture);
+                }
+                for player in player_reports.iter() {
+                    println!(
+                        "{} {}: {} ({}/{} combos)",
+                        player.position,
+                        player.action,
+                        player.range,
+                        player.combos,
+                        player.range_combos
+                    );
+                }
+                match (&relative_hand, &madehand) {
+                    (Ok(relative_hand), Ok(madehand)) => {
+                        println!("{}: {}", relative_hand, madehand)
+                    }
+                    (_, Ok(madehand)) => println!("{}", madehand),
+                    // No made hand before the flop.
+                    (_, Err(_)) => {}
+                }
+                for (draw, cards) in outs.draws.iter() {
+                    if cards.is_empty() {
+                        println!("{}", draw);
+                        continue;
+                    }
+                    let cards = cards
+                        .iter()
+                        .map(|card| card.to_string())
+                        .collect::<Vec<_>>();
+                    println!("{}: {}", draw, cards.join(" "));
+                }
+                if !outs.cards.is_empty() {
+                    println!(
+                        "Outs: {} / Turn: {} / River: {}",
+                        outs.cards.len(),
+                        outs.turn
+                            .map_or("-".to_string(), |p| format!("{:?}", round(p))),
+                        outs.river
+                            .map_or("-".to_string(), |p| format!("{:?}", round(p)))
+                    );
+                }
+                match equity {
+                    Ok(equity) => print_equity(&equity),
+                    Err(err) => println!("{}", err),
+                }
+                if player_reports.len() > 2 {
+                    let equities = player_reports
+                        .iter()
+                        .filter_map(|player| {
+                            player
+                                .equity
+                                .map(|equity| format!("{} {:?}", player.position, round(equity)))
+                        })
+                        .collect::<Vec<_>>();
+                    println!("Equities: {}", equities.join(" / "));
+                }
+                if !distributions.is_empty() {
+                    print_distributions(&distributions);
+                }
+                if let Some(position) = &range_advantage {
+                    println!("Range Advantage: {}", position);
+                }
+                if let Some(position) = &nut_advantage {
+                    println!("Nut Advantage: {}", position);
+                }
+                match hand_equity {
+                    Ok(equity) => println!("Hand Equity: {:?}", round(equity.equity)),
+                    Err(err) => println!("{}", err),
+                }
+                breakdown.iter().for_each(|part| {
+                    println!(
+                        "- {} ({}): {:?}",
+                        part.hand,
+                        part.combos,
+                        round(part.equity)
+                    )
+                });
+                if let Some(odds) = &odds {
+                    println!(
+                        "Pot Odds: {}bb into {}bb, break-even {:?} / equity {:?} -> {}",
+                        odds.bet,
+                        odds.pot,
+                        round(odds.break_even),
+                        round(odds.equity),
+                        odds.verdict
+                    );
+                    if let Some(implied) = &odds.implied {
+                        println!(
+                            "Implied Odds: {:?}bb more needed, {}bb behind -> {}",
+                            round(implied.needed),
+                            implied.stack_behind,
+                            implied.verdict
+                        );
+                    }
+                }
+            }
+            OutputFormat::Json => {
+                for err in [&equity, &hand_equity]
+                    .iter()
+                    .filter_map(|e| e.as_ref().err())
+                {
+                    eprintln!("{}", err);
+                }
+                let mut player_reports = player_reports.into_iter();
+                let report = DuelReport {
+                    version: SCHEMA_VERSION,
+                    board: board.to_string(),
+                    street: street.to_string(),
+                    texture,
+                    stack: self.stack,
+                    pot,
+                    spr,
+                    hero: player_reports.next().unwrap(),
+                    villain: player_reports.next().unwrap(),
+                    other_villains: player_reports.collect(),
+                    hand: relative_hand.ok().map(|hand| hand.to_string()),
+                    made_hand: madehand.ok().map(|madehand| madehand.to_string()),
+                    draws: drawhands
+                        .iter()
+                        .map(|drawhand| drawhand.to_string())
+                        .collect(),
+                    outs: OutsReport::from(&outs),
+                    equity: equity.ok(),
+                    hand_equity: hand_equity.ok(),
+                    breakdown,
+                    ranges,
+                    range_advantage,
+                    nut_advantage,
+                    odds,
+                };
+                println!("{}", report.to_json()?);
+            }
+        }
+        Ok(())
     }
 
-    fn create_hero_and_villain(&self) -> Result<(Player, Player), String> {
-        let hero = Player::new(
-            Some(self.hand.hand.clone()),
-            self.hero_position.clone(),
-            self.villain_positon.clone(),
-            self.villain_action.clone(),
-        );
-        let villain = Player::new(
-            None,
-            self.villain_positon,
-            self.hero_position,
-            self.villain_action.to_hero_action(),
-        );
-        match (hero, villain) {
-            (Ok(hero), Ok(villain)) => Ok((hero, villain)),
-            (Err(error), _) => Err(error.to_string()),
-            (_, Err(error)) => Err(error.to_string()),
+    /// Hero's hand against each part of villain's range, best for hero first.
+    fn breakdown(
+        &self,
+        breakdown: Breakdown,
+        hero_hand_range: &HandRange,
+        villain: &Player,
+        exact: bool,
+    ) -> Vec<BreakdownReport> {
+        let board = self.board();
+        let dead_mask = self.create_available_cards().unwrap().mask();
+        let parts = equity::split_range(&villain.hand_range, dead_mask, |combo| match breakdown {
+            Breakdown::Combo => combo.to_string(),
+            Breakdown::Class => HandClass::from_combo(combo).to_string(),
+        });
+        let mut reports: Vec<BreakdownReport> = parts
+            .into_iter()
+            .filter_map(|(hand, part)| {
+                analyse(hero_hand_range, &[&part], &board, exact)
+                    .ok()
+                    .map(|(equity, _)| BreakdownReport {
+                        hand,
+                        combos: part.hands.len(),
+                        equity: equity.equity,
+                    })
+            })
+            .collect();
+        reports.sort_by(|a, b| b.equity.partial_cmp(&a.equity).unwrap());
+        reports
+    }
+
+    /// The preflop line that led to this pot.
+    fn line(&self) -> Result<PreflopLine, String> {
+        match &self.villain_action {
+            VillainAction::Action(action) => Ok(PreflopLine::heads_up(
+                self.hero_position,
+                self.villain_positon,
+                *action,
+            )),
+            VillainAction::Line(line) => match [self.hero_position, self.villain_positon]
+                .iter()
+                .find(|position| !line.players().contains(position))
+            {
+                Some(position) => Err(format!("{} doesn't act in {}", position, line)),
+                None => Ok(line.clone()),
+            },
         }
     }
 
+    /// Pot odds for hero's hand facing `bet` into `pot`. With a draw the pot odds don't pay
+    /// for, the implied odds are checked against what the players have behind after the call.
+    fn odds(
+        &self,
+        line: &PreflopLine,
+        pot: f64,
+        bet: f64,
+        equity: f64,
+        has_draw: bool,
+    ) -> OddsReport {
+        let verdict = odds::verdict(equity, pot, bet);
+        let implied = if has_draw && verdict == Verdict::Fold {
+            let stack_behind = (self.stack - self.put_in(line) - bet).max(0.0);
+            Some(ImpliedOddsReport {
+                needed: odds::implied_odds_needed(equity, pot, bet),
+                stack_behind,
+                verdict: odds::implied_verdict(equity, pot, bet, stack_behind).to_string(),
+            })
+        } else {
+            None
+        };
+        OddsReport {
+            pot,
+            bet,
+            break_even: odds::break_even_equity(pot, bet),
+            equity,
+            verdict: verdict.to_string(),
+            implied,
+        }
+    }
+
+    /// What hero put in preflop, in bb.
+    fn put_in(&self, line: &PreflopLine) -> f64 {
+        line.contributions(self.stack)
+            .iter()
+            .find(|(position, _)| position == &self.hero_position)
+            .map_or(0.0, |(_, chips)| *chips)
+    }
+
+    /// Hero's stack behind over the pot once the line is played.
+    fn spr(&self, line: &PreflopLine) -> f64 {
+        (self.stack - self.put_in(line)) / line.pot(self.stack)
+    }
+
+    /// Hero, then the main villain and the other villains. Another villain's chart is found
+    /// for their action against the last raiser of `line`; in an unraised pot they limp.
+    fn create_players(
+        &self,
+        ranges: &[Range],
+        line: &PreflopLine,
+    ) -> Result<(Player, Vec<Player>), String> {
+        let player = |line: &PreflopLine, position, hand, profile: &Option<Profile>| {
+            Player::new(
+                ranges,
+                hand,
+                line,
+                position,
+                profile.clone().unwrap_or_else(|| self.profile.clone()),
+                self.table,
+                self.stack,
+            )
+            .map_err(|error| error.to_string())
+        };
+        let hero = player(
+            line,
+            self.hero_position,
+            Some(self.hand.hand.clone()),
+            &self.hero_profile,
+        )?;
+        let villain = player(line, self.villain_positon, None, &self.villain_profile)?;
+        let raiser = line.last_raiser();
+        let others = self
+            .other_villains
+            .iter()
+            .map(|spot| {
+                let line = match raiser {
+                    Some(raiser) => PreflopLine::from_spot(spot.position, raiser, spot.action),
+                    None => PreflopLine::from_spot(spot.position, Position::NONE, Action::Limp),
+                };
+                player(&line, spot.position, None, &self.villain_profile)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok((hero, std::iter::once(villain).chain(others).collect()))
+    }
+
+    /// The board, empty preflop.
+    fn board(&self) -> Board {
+        self.board.clone().unwrap_or_default()
+    }
+
     fn create_available_cards(&self) -> Result<Cards, String> {
-        Ok(self.hand.cards.clone() + self.board.cards.clone())
+        let board = self.board();
+        self.hand
+            .cards
+            .union(board.cards())
+            .map_err(|_| format!("{} shares a card with the board", self.hand.cards))
+    }
+}
+
+/// Hero's equity, and every player's equity starting with hero's. Only heads-up pots are
+/// enumerated exactly; multiway pots are always sampled.
+fn analyse(
+    hero_hand_range: &HandRange,
+    villain_hand_ranges: &[&HandRange],
+    board: &Board,
+    exact: bool,
+) -> Result<(EquityReport, Vec<f64>), String> {
+    let board_mask = board.cards().mask();
+    if let (true, [villain_hand_range]) = (exact, villain_hand_ranges) {
+        return equity::exact_equity(hero_hand_range, villain_hand_range, board_mask)
+            .map(|equity| {
+                let report = EquityReport {
+                    exact: true,
+                    equity: equity.equity(),
+                    win: Some(equity.win),
+                    tie: Some(equity.tie),
+                    lose: Some(equity.lose),
+                };
+                (report, vec![equity.equity(), 1.0 - equity.equity()])
+            })
+            .map_err(|err| err.to_string());
+    }
+    let hand_ranges = std::iter::once(hero_hand_range)
+        .chain(villain_hand_ranges.iter().copied())
+        .cloned()
+        .collect::<Vec<_>>();
+    rust_poker::equity_calculator::approx_equity(&hand_ranges, board_mask, 4, 1000.0)
+        .map(|equities| {
+            let report = EquityReport {
+                exact: false,
+                equity: equities[0],
+                win: None,
+                tie: None,
+                lose: None,
+            };
+            (report, equities)
+        })
+        .map_err(|err| err.to_string())
+}
+
+fn print_equity(equity: &EquityReport) {
+    match (equity.win, equity.tie, equity.lose) {
+        (Some(win), Some(tie), Some(lose)) => {
+            println!("Win Rate: {:?} (exact)", round(equity.equity));
+            println!(
+                "Win: {:?} / Tie: {:?} / Lose: {:?}",
+                round(win),
+                round(tie),
+                round(lose)
+            );
+        }
+        _ => println!("Win Rate: {:?}", round(equity.equity)),
+    }
+}
+
+/// One row per category any range has, with each range's combos and share side by side.
+fn print_distributions(distributions: &[(Position, Distribution)]) {
+    let categories = distributions
+        .iter()
+        .flat_map(|(_, distribution)| distribution.categories.iter().map(|(c, _)| *c))
+        .collect::<BTreeSet<_>>();
+    let header = distributions
+        .iter()
+        .map(|(position, _)| format!("{:>15}", position.to_string()))
+        .collect::<String>();
+    println!("{:<26}{}", "Range vs Board", header);
+    for category in categories.iter().rev() {
+        let row = distributions
+            .iter()
+            .map(|(_, distribution)| {
+                let combos = distribution
+                    .categories
+                    .iter()
+                    .find(|(c, _)| c == category)
+                    .map_or(0, |(_, combos)| *combos);
+                format!(
+                    "{:>6} ({:>5.1}%)",
+                    combos,
+                    distribution.share(combos) * 100.0
+                )
+            })
+            .collect::<String>();
+        println!("{:<26}{}", category.to_string(), row);
     }
 }
 
-fn analyse(hero_hand_range: HandRange, villain_hand_range: HandRange, board: &Board) {
-    let result = rust_poker::equity_calculator::approx_equity(
-        &[hero_hand_range, villain_hand_range].to_vec(),
-        get_card_mask(&board.cards.text),
-        4,
-        1000.0,
-    );
-    match result {
-        Ok(equities) => println!("Win Rate: {:?}", (equities[0] * 100.0).round() / 100.0),
-        Err(err) => println!("{}", err),
+fn round(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    use crate::core::range;
+
+    #[test]
+    fn test_villain_spot() {
+        let spot = VillainSpot::from_str("btn:call").unwrap();
+        assert_eq!(spot.position, Position::BTN);
+        assert_eq!(spot.action, Action::Call);
+        assert!(VillainSpot::from_str("btn").is_err());
+        assert!(VillainSpot::from_str("btn:fold").is_err());
+    }
+
+    #[test]
+    fn test_multiway_equity() {
+        let board = Board::from_str("2c7s9d").unwrap();
+        let range = |text: &str| HandRange::from_string(text.to_string());
+        let (hero, villain, other) = (range("AhAd"), range("KhKd"), range("4h3d"));
+        let (report, equities) = analyse(&hero, &[&villain, &other], &board, true).unwrap();
+        assert!(!report.exact);
+        assert_eq!(equities.len(), 3);
+        assert!((equities.iter().sum::<f64>() - 1.0).abs() < 1e-6);
+        assert!(equities[0] > equities[1] && equities[1] > equities[2]);
+
+        let (report, equities) = analyse(&hero, &[&villain], &board, true).unwrap();
+        assert!(report.exact);
+        assert_eq!(equities[0] + equities[1], 1.0);
+    }
+
+    #[test]
+    fn test_preflop_duel() {
+        let duel = Duel::try_parse_from(["duel", "btn", "AhKh", "bb", "call", "--output", "json"])
+            .unwrap();
+        assert_eq!(duel.board().street(), Street::Preflop);
+        let ranges = range::read_ranges(Some(Path::new("range.json"))).unwrap();
+        assert!(duel.analyse(&ranges).is_ok());
+
+        let exact = Duel::try_parse_from(["duel", "btn", "AhKh", "bb", "call", "--exact"]).unwrap();
+        assert!(exact.analyse(&ranges).is_err());
+    }
+
+    #[test]
+    fn test_limped_multiway() {
+        let duel = Duel::try_parse_from([
+            "duel",
+            "bb",
+            "AhKh",
+            "sb",
+            "limp",
+            "2c8s9d",
+            "--villain",
+            "btn:call",
+        ])
+        .unwrap();
+        let ranges = range::read_ranges(Some(Path::new("range.json"))).unwrap();
+        let line = duel.line().unwrap();
+        let (_, villains) = duel.create_players(&ranges, &line).unwrap();
+        assert_eq!(villains.len(), 2);
+        assert_eq!(villains[1].position, Position::BTN);
+        assert_eq!(villains[1].action, Action::Limp);
+        let line = line.with_callers(&[Position::BTN], duel.table);
+        assert_eq!(line.to_string(), "btn-limp sb-limp bb-limp");
+        assert_eq!(line.pot(duel.stack), 3.0);
+    }
+
+    #[test]
+    fn test_implied_odds_only_when_pot_odds_fold() {
+        let duel = Duel::try_parse_from([
+            "duel", "bb", "Th9h", "co", "open", "2c8s7h", "--pot", "5.5", "--bet", "4",
+        ])
+        .unwrap();
+        let line = duel.line().unwrap();
+        let favourite = duel.odds(&line, 5.5, 4.0, 0.93, true);
+        assert_eq!(favourite.verdict, "call");
+        assert!(favourite.implied.is_none());
+        let drawing = duel.odds(&line, 5.5, 4.0, 0.2, true);
+        assert_eq!(drawing.verdict, "fold");
+        assert!(drawing.implied.is_some());
+        assert!(duel.odds(&line, 5.5, 4.0, 0.2, false).implied.is_none());
     }
 }
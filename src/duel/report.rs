@@ -0,0 +1,360 @@
+use serde::Serialize;
+use std::{fmt, str::FromStr};
+
+use crate::board::report::TextureReport;
+use crate::core::{distribution::Distribution, drawhand::Outs, player::Player};
+
+/// Bumped whenever a field of `DuelReport` is renamed, removed or changes meaning.
+pub const SCHEMA_VERSION: u32 = 5;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum OutputFormat {
+    Text,
+    Json,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match &s.to_ascii_lowercase()[..] {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            _ => Err(format!("Invalid output format: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OutputFormat::Text => write!(f, "text"),
+            OutputFormat::Json => write!(f, "json"),
+        }
+    }
+}
+
+/// How the breakdown of villain's range is grouped.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Breakdown {
+    Combo,
+    Class,
+}
+
+impl FromStr for Breakdown {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match &s.to_ascii_lowercase()[..] {
+            "combo" => Ok(Self::Combo),
+            "class" => Ok(Self::Class),
+            _ => Err(format!("Invalid breakdown: {}", s)),
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct DuelReport {
+    pub version: u32,
+    pub board: String,
+    /// preflop, flop, turn or river.
+    pub street: String,
+    /// None preflop.
+    pub texture: Option<TextureReport>,
+    /// Effective stack in bb at the start of the hand.
+    pub stack: f64,
+    /// Pot in bb when the flop is dealt.
+    pub pot: f64,
+    pub spr: f64,
+    pub hero: PlayerReport,
+    pub villain: PlayerReport,
+    /// Villains added with `--villain`, in the order given.
+    pub other_villains: Vec<PlayerReport>,
+    /// Hero's made hand relative to the board, such as "top pair, good kicker".
+    pub hand: Option<String>,
+    pub made_hand: Option<String>,
+    pub draws: Vec<String>,
+    pub outs: OutsReport,
+    pub equity: Option<EquityReport>,
+    /// Equity of hero's actual hole cards against villain's range.
+    pub hand_equity: Option<EquityReport>,
+    pub breakdown: Vec<BreakdownReport>,
+    /// Each player's range on the board, hero first.
+    pub ranges: Vec<DistributionReport>,
+    /// Position with the most top pair or better.
+    pub range_advantage: Option<String>,
+    /// Position with the most sets or better.
+    pub nut_advantage: Option<String>,
+    /// Only with `--bet`.
+    pub odds: Option<OddsReport>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct PlayerReport {
+    pub position: String,
+    pub action: String,
+    pub profile: String,
+    pub range: String,
+    /// Combos left after removing the board and hero's hole cards.
+    pub combos: usize,
+    /// Combos in the chart before card removal.
+    pub range_combos: usize,
+    /// Share of the pot this player's range wins.
+    pub equity: Option<f64>,
+}
+
+impl From<&Player> for PlayerReport {
+    fn from(player: &Player) -> Self {
+        PlayerReport {
+            position: player.position.to_string(),
+            action: player.action.to_string(),
+            profile: player.profile.to_string(),
+            range: player.range_name.clone(),
+            combos: player.live_combos(),
+            range_combos: player.range_combos,
+            equity: None,
+        }
+    }
+}
+
+/// Cards that complete hero's draws, with the board and hole cards removed.
+#[derive(Serialize, Debug, Default)]
+pub struct OutsReport {
+    pub draws: Vec<DrawOutsReport>,
+    /// Each out once, even if it completes more than one draw.
+    pub count: usize,
+    pub cards: Vec<String>,
+    /// Chance of hitting on the turn, only on the flop.
+    pub turn: Option<f64>,
+    /// Chance of hitting by the river.
+    pub river: Option<f64>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct DrawOutsReport {
+    pub draw: String,
+    pub outs: Vec<String>,
+}
+
+impl From<&Outs> for OutsReport {
+    fn from(outs: &Outs) -> Self {
+        OutsReport {
+            draws: outs
+                .draws
+                .iter()
+                .map(|(draw, cards)| DrawOutsReport {
+                    draw: draw.to_string(),
+                    outs: cards.iter().map(|card| card.to_string()).collect(),
+                })
+                .collect(),
+            count: outs.cards.len(),
+            cards: outs.cards.iter().map(|card| card.to_string()).collect(),
+            turn: outs.turn,
+            river: outs.river,
+        }
+    }
+}
+
+/// Hero's equity. `win`, `tie` and `lose` are only known when runouts were enumerated exactly.
+#[derive(Serialize, Debug)]
+pub struct EquityReport {
+    pub exact: bool,
+    pub equity: f64,
+    pub win: Option<f64>,
+    pub tie: Option<f64>,
+    pub lose: Option<f64>,
+}
+
+/// Hero's equity against one combo or hand class of villain's range.
+#[derive(Serialize, Debug)]
+pub struct BreakdownReport {
+    pub hand: String,
+    pub combos: usize,
+    pub equity: f64,
+}
+
+/// What a player's range has on the board, strongest category first.
+#[derive(Serialize, Debug)]
+pub struct DistributionReport {
+    pub position: String,
+    pub combos: usize,
+    pub categories: Vec<CategoryReport>,
+    /// Share of top pair or better.
+    pub value: f64,
+    /// Share of sets or better.
+    pub nuts: f64,
+}
+
+#[derive(Serialize, Debug)]
+pub struct CategoryReport {
+    pub category: String,
+    pub combos: usize,
+    pub share: f64,
+}
+
+impl DistributionReport {
+    pub fn new(position: String, distribution: &Distribution) -> Self {
+        DistributionReport {
+            position,
+            combos: distribution.combos,
+            categories: distribution
+                .categories
+                .iter()
+                .map(|(category, combos)| CategoryReport {
+                    category: category.to_string(),
+                    combos: *combos,
+                    share: distribution.share(*combos),
+                })
+                .collect(),
+            value: distribution.value_share(),
+            nuts: distribution.nut_share(),
+        }
+    }
+}
+
+/// Position of the range with the largest `share`, none when every range has the same.
+pub fn advantage(
+    ranges: &[DistributionReport],
+    share: impl Fn(&DistributionReport) -> f64,
+) -> Option<String> {
+    let best = ranges
+        .iter()
+        .max_by(|a, b| share(a).partial_cmp(&share(b)).unwrap())?;
+    if ranges.iter().all(|range| share(range) == share(best)) {
+        return None;
+    }
+    Some(best.position.clone())
+}
+
+/// Whether hero's hand can call the bet. Amounts are in bb.
+#[derive(Serialize, Debug)]
+pub struct OddsReport {
+    pub pot: f64,
+    pub bet: f64,
+    pub break_even: f64,
+    /// Hero's hand equity against the villains' ranges.
+    pub equity: f64,
+    pub verdict: String,
+    /// Only when hero has a draw.
+    pub implied: Option<ImpliedOddsReport>,
+}
+
+/// What hero must win on later streets to make the call, against what is left to win.
+#[derive(Serialize, Debug)]
+pub struct ImpliedOddsReport {
+    pub needed: f64,
+    pub stack_behind: f64,
+    pub verdict: String,
+}
+
+impl DuelReport {
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rs_poker::core::{Suit, Value};
+
+    use crate::core::{board::Board, cards::Cards, drawhand::DrawHand, madehand::MadeHand};
+
+    #[test]
+    fn test_output_format() {
+        assert_eq!(OutputFormat::from_str("JSON"), Ok(OutputFormat::Json));
+        assert!(OutputFormat::from_str("yaml").is_err());
+        assert_eq!(Breakdown::from_str("class"), Ok(Breakdown::Class));
+    }
+
+    #[test]
+    fn test_advantage() {
+        let range = |position: &str, value: f64, nuts: f64| DistributionReport {
+            position: position.to_string(),
+            combos: 100,
+            categories: vec![],
+            value,
+            nuts,
+        };
+        let ranges = [range("BTN", 0.3, 0.05), range("BB", 0.2, 0.05)];
+        assert_eq!(advantage(&ranges, |r| r.value), Some("BTN".to_string()));
+        assert_eq!(advantage(&ranges, |r| r.nuts), None);
+    }
+
+    #[test]
+    fn test_to_json() {
+        let player = || PlayerReport {
+            position: "BTN".to_string(),
+            action: "Open".to_string(),
+            profile: "standard".to_string(),
+            range: "BTNオープン".to_string(),
+            combos: 10,
+            range_combos: 12,
+            equity: Some(0.5),
+        };
+        let report = DuelReport {
+            version: SCHEMA_VERSION,
+            board: "4c5c6d".to_string(),
+            street: "flop".to_string(),
+            texture: Some(TextureReport::new(&Board::from_str("4c5c6d").unwrap())),
+            stack: 100.0,
+            pot: 6.5,
+            spr: 15.0,
+            hero: player(),
+            villain: player(),
+            other_villains: vec![],
+            hand: Some("overpair".to_string()),
+            made_hand: Some(MadeHand::TwoPair(Value::King, Value::Seven, Value::Ace).to_string()),
+            draws: vec![DrawHand::FlashDraw(Suit::Heart).to_string()],
+            outs: OutsReport::from(&Outs {
+                draws: vec![(
+                    DrawHand::StraightDraw(Value::Seven),
+                    Cards::from_str("7s").unwrap().cards().to_vec(),
+                )],
+                cards: Cards::from_str("7s").unwrap().cards().to_vec(),
+                turn: Some(0.02),
+                river: Some(0.04),
+            }),
+            equity: Some(EquityReport {
+                exact: true,
+                equity: 0.5,
+                win: Some(0.4),
+                tie: Some(0.2),
+                lose: Some(0.4),
+            }),
+            hand_equity: None,
+            breakdown: vec![BreakdownReport {
+                hand: "AKs".to_string(),
+                combos: 3,
+                equity: 0.25,
+            }],
+            ranges: vec![],
+            range_advantage: Some("BTN".to_string()),
+            nut_advantage: None,
+            odds: Some(OddsReport {
+                pot: 6.5,
+                bet: 4.0,
+                break_even: 0.28,
+                equity: 0.25,
+                verdict: "fold".to_string(),
+                implied: None,
+            }),
+        };
+        let json: serde_json::Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+        assert_eq!(json["version"], SCHEMA_VERSION);
+        assert_eq!(json["pot"], 6.5);
+        assert_eq!(json["texture"]["suitedness"], "two-tone");
+        assert_eq!(json["hero"]["range"], "BTNオープン");
+        assert_eq!(json["villain"]["equity"], 0.5);
+        assert_eq!(json["equity"]["tie"], 0.2);
+        assert_eq!(json["hand"], "overpair");
+        assert_eq!(json["made_hand"], "two pair (KK77A)");
+        assert_eq!(json["draws"][0], "flush draw (h)");
+        assert_eq!(json["outs"]["draws"][0]["draw"], "straight draw (7)");
+        assert_eq!(json["outs"]["draws"][0]["outs"][0], "7s");
+        assert_eq!(json["outs"]["count"], 1);
+        assert_eq!(json["breakdown"][0]["hand"], "AKs");
+        assert_eq!(json["range_advantage"], "BTN");
+        assert!(json["nut_advantage"].is_null());
+        assert_eq!(json["odds"]["verdict"], "fold");
+        assert!(json["odds"]["implied"].is_null());
+    }
+}
@@ -3,14 +3,32 @@ use std::process::exit;
 
 use rust_poker::hand_range::HandRange;
 
-use crate::core::{position::Position, range};
+use crate::core::{
+    position::{Position, TableSize},
+    profile::Profile,
+    range::{self, Range},
+};
 
 #[derive(Clap)]
-pub struct Hand {}
+pub struct Hand {
+    /// Only show the charts used by this range profile
+    #[clap(long)]
+    pub profile: Option<Profile>,
+    /// Table size the charts are for: 3max, 4max, 5max, 6max or 9max
+    #[clap(long, default_value = "6max")]
+    pub table: TableSize,
+}
 
 impl Hand {
-    pub fn analyse(&self) -> () {
-        println!("{}", "input below.");
+    pub fn analyse(&self, ranges: &[Range]) {
+        let ranges: Vec<&Range> = match &self.profile {
+            Some(profile) => range::select_profile(ranges, profile, self.table),
+            None => ranges
+                .iter()
+                .filter(|range| range.table() == self.table)
+                .collect(),
+        };
+        println!("input below.");
         loop {
             println!("================================================");
             let mut args = String::new();
@@ -22,7 +40,7 @@ impl Hand {
                 match vars.len() {
                     1 | 2 => {
                         let position = vars.get(1).and_then(|var| Position::from(var));
-                        hand_analyse(position, vars[0].to_string());
+                        hand_analyse(&ranges, position, vars[0].to_string());
                     }
                     _ => println!("Invalid args."),
                 }
@@ -30,10 +48,10 @@ impl Hand {
         }
     }
 }
-fn hand_analyse(position: Option<Position>, hand: String) -> () {
-    let sample_combos = HandRange::from_string(hand.clone()).hands;
+fn hand_analyse(ranges: &[&Range], position: Option<Position>, hand: String) {
+    let sample_combos = HandRange::from_string(hand).hands;
 
-    range::read_ranges()
+    ranges
         .iter()
         .filter(|range| match position {
             None => true,
@@ -44,5 +62,12 @@ fn hand_analyse(position: Option<Position>, hand: String) -> () {
                 .iter()
                 .all(|combo| range.contains_combo(combo))
         })
-        .for_each(|range| println!("{}", range.to_string(None)));
+        .for_each(|range| {
+            let frequency = sample_combos
+                .iter()
+                .map(|combo| range.frequency(combo))
+                .sum::<f64>()
+                / sample_combos.len() as f64;
+            println!("{} {:.0}%", range.to_string(None), frequency * 100.0)
+        });
 }
@@ -1,7 +1,13 @@
 pub mod argparse;
 
+pub mod board;
+
 pub mod hand;
 
 pub mod duel;
 
+pub mod range;
+
+pub mod showdown;
+
 pub mod core;
@@ -1,10 +1,16 @@
 use headsup_analyzer::argparse::{arg_parse, SubCommand};
+use headsup_analyzer::core::range;
 
-fn main() {
+fn main() -> anyhow::Result<()> {
     let opts = arg_parse();
+    let path = opts.ranges.as_deref();
 
     match opts.sub_command {
-        SubCommand::Duel(duel) => duel.analyse(),
-        SubCommand::Hand(hand) => hand.analyse(),
+        SubCommand::Duel(duel) => duel.analyse(&range::read_ranges(path)?)?,
+        SubCommand::Hand(hand) => hand.analyse(&range::read_ranges(path)?),
+        SubCommand::Range(range) => range.analyse(path)?,
+        SubCommand::Board(board) => board.analyse()?,
+        SubCommand::Showdown(showdown) => showdown.analyse()?,
     }
+    Ok(())
 }
@@ -0,0 +1,136 @@
+use anyhow::bail;
+use clap::Clap;
+use std::{path::Path, str::FromStr};
+
+use crate::core::{
+    action::Action,
+    grid,
+    line::PreflopLine,
+    position::{Position, TableSize},
+    profile::Profile,
+    range::{self, Range},
+};
+
+#[derive(Clap)]
+pub struct RangeCommand {
+    #[clap(subcommand)]
+    pub sub_command: RangeSubCommand,
+}
+
+#[derive(Clap)]
+pub enum RangeSubCommand {
+    /// Render a chart as a 13x13 grid
+    Show(Show),
+    /// Check every entry of the range chart and report the invalid ones
+    Validate(Validate),
+}
+
+#[derive(Clap)]
+pub struct Show {
+    /// Chart name, a preflop line such as "utg-open co-call btn-3bet", or POSITION ACTION [OPPONENT]
+    #[clap(name = "RANGE", required = true, min_values = 1, max_values = 3)]
+    pub query: Vec<String>,
+    /// Range profile to look the chart up with
+    #[clap(long, default_value = "standard")]
+    pub profile: Profile,
+    /// Table size the chart is for: 3max, 4max, 5max, 6max or 9max
+    #[clap(long, default_value = "6max")]
+    pub table: TableSize,
+    /// Effective stack in bb, to pick between charts for different depths
+    #[clap(long)]
+    pub stack: Option<f64>,
+    /// Print without ANSI colors
+    #[clap(long)]
+    pub no_color: bool,
+}
+
+#[derive(Clap)]
+pub struct Validate {}
+
+impl RangeCommand {
+    pub fn analyse(&self, path: Option<&Path>) -> anyhow::Result<()> {
+        match &self.sub_command {
+            RangeSubCommand::Show(show) => show.analyse(&range::read_ranges(path)?)?,
+            RangeSubCommand::Validate(validate) => validate.analyse(path)?,
+        }
+        Ok(())
+    }
+}
+
+impl Validate {
+    pub fn analyse(&self, path: Option<&Path>) -> anyhow::Result<()> {
+        let errors = range::validate_ranges(path)?;
+        if errors.is_empty() {
+            println!("OK");
+            return Ok(());
+        }
+        errors.iter().for_each(|error| println!("{}", error));
+        bail!("{} invalid entries", errors.len())
+    }
+}
+
+impl Show {
+    pub fn analyse(&self, ranges: &[Range]) -> anyhow::Result<()> {
+        let range = self.find(ranges).map_err(anyhow::Error::msg)?;
+        println!("{}", range.name());
+        println!("{}", grid::render_grid(range.hand_range(), !self.no_color));
+        let combos = range::combo_count(range.hand_range());
+        let weighted = range::weighted_combo_count(range.hand_range());
+        if weighted < combos as f64 {
+            println!(
+                "{} combos, {:.1} by frequency ({:.1}%)",
+                combos,
+                weighted,
+                grid::combo_percentage(range.hand_range())
+            );
+        } else {
+            println!(
+                "{} combos ({:.1}%)",
+                combos,
+                grid::combo_percentage(range.hand_range())
+            );
+        }
+        Ok(())
+    }
+
+    fn find<'a>(&self, ranges: &'a [Range]) -> Result<&'a Range, String> {
+        if let [name] = &self.query[..] {
+            if let Some(range) = ranges.iter().find(|range| range.name() == name) {
+                return Ok(range);
+            }
+            if name.contains('-') {
+                let line = PreflopLine::from_str(name)?;
+                return range::find_line_range(
+                    ranges,
+                    &line,
+                    &self.profile,
+                    self.table,
+                    self.stack,
+                )
+                .ok_or_else(|| {
+                    format!(
+                        "can't find range: {} ({}, {})",
+                        line, self.profile, self.table
+                    )
+                });
+            }
+        }
+        let (me, action, opponent) = match &self.query[..] {
+            [me, action] => (me, action, "none"),
+            [me, action, opponent] => (me, action, &opponent[..]),
+            _ => return Err(format!("can't find range: {}", self.query.join(" "))),
+        };
+        let me = Position::from_str(me)?;
+        let action = Action::from_str(action)?;
+        let opponent = Position::from_str(opponent)?;
+        let line = PreflopLine::from_spot(me, opponent, action);
+        range::find_line_range(ranges, &line, &self.profile, self.table, self.stack).ok_or_else(
+            || {
+                format!(
+                    "can't find range: {} {} vs {} ({}, {})",
+                    me, action, opponent, self.profile, self.table
+                )
+            },
+        )
+    }
+}
@@ -0,0 +1,70 @@
+use serde::Serialize;
+
+use crate::core::{
+    equity::Equity,
+    showdown::{BestHand, Showdown},
+};
+
+#[derive(Serialize, Debug)]
+pub struct ShowdownReport {
+    pub board: String,
+    pub hero: BestHandReport,
+    pub villain: BestHandReport,
+    /// Hero's result on this board: win, lose or tie.
+    pub result: String,
+    /// Hero's share of every runout, before the river.
+    pub equity: Option<RunoutEquityReport>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct BestHandReport {
+    pub hole_cards: String,
+    pub made_hand: String,
+    /// The best 5 cards out of the hole cards and the board.
+    pub cards: Vec<String>,
+    /// Higher is stronger, from 1 to 7462.
+    pub rank: u16,
+}
+
+#[derive(Serialize, Debug)]
+pub struct RunoutEquityReport {
+    pub win: f64,
+    pub tie: f64,
+    pub lose: f64,
+}
+
+impl BestHandReport {
+    pub fn new(hole_cards: String, best: &BestHand) -> Self {
+        BestHandReport {
+            hole_cards,
+            made_hand: best.made_hand.to_string(),
+            cards: best.cards.iter().map(|card| card.to_string()).collect(),
+            rank: best.rank.value(),
+        }
+    }
+}
+
+impl ShowdownReport {
+    pub fn new(
+        board: String,
+        hole_cards: (String, String),
+        showdown: &Showdown,
+        equity: Option<Equity>,
+    ) -> Self {
+        ShowdownReport {
+            board,
+            hero: BestHandReport::new(hole_cards.0, &showdown.hero),
+            villain: BestHandReport::new(hole_cards.1, &showdown.villain),
+            result: showdown.outcome.to_string(),
+            equity: equity.map(|equity| RunoutEquityReport {
+                win: equity.win,
+                tie: equity.tie,
+                lose: equity.lose,
+            }),
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
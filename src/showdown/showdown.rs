@@ -0,0 +1,71 @@
+use anyhow::anyhow;
+use clap::Clap;
+
+use crate::core::{board::Board, cards::Cards};
+use crate::duel::report::OutputFormat;
+use crate::showdown::report::{BestHandReport, ShowdownReport};
+
+#[derive(Clap)]
+pub struct ShowdownCommand {
+    /// Hero's hole cards such as "AhKd"
+    #[clap(name = "HERO_CARDS")]
+    pub hero: Cards,
+    /// Villain's hole cards
+    #[clap(name = "VILLAIN_CARDS")]
+    pub villain: Cards,
+    /// Flop, turn or river. Before the river the equity over every runout is shown too
+    #[clap(name = "BOARD")]
+    pub board: Board,
+    /// Output format: text or json
+    #[clap(long, default_value = "text")]
+    pub output: OutputFormat,
+}
+
+impl ShowdownCommand {
+    pub fn analyse(&self) -> anyhow::Result<()> {
+        let showdown = self
+            .board
+            .showdown(&self.hero, &self.villain)
+            .map_err(|err| anyhow!(err))?;
+        let equity = if self.board.cards_to_come() > 0 {
+            Some(
+                self.board
+                    .runout_equity(&self.hero, &self.villain)
+                    .map_err(|err| anyhow!(err))?,
+            )
+        } else {
+            None
+        };
+        let report = ShowdownReport::new(
+            self.board.to_string(),
+            (self.hero.to_string(), self.villain.to_string()),
+            &showdown,
+            equity,
+        );
+        match self.output {
+            OutputFormat::Text => {
+                print_best_hand("Hero", &report.hero);
+                print_best_hand("Villain", &report.villain);
+                println!("Result: {}", report.result);
+                if let Some(equity) = &report.equity {
+                    println!(
+                        "Runouts: win {:.3} / tie {:.3} / lose {:.3}",
+                        equity.win, equity.tie, equity.lose
+                    );
+                }
+            }
+            OutputFormat::Json => println!("{}", report.to_json()?),
+        }
+        Ok(())
+    }
+}
+
+fn print_best_hand(player: &str, hand: &BestHandReport) {
+    println!(
+        "{} {}: {} [{}]",
+        player,
+        hand.hole_cards,
+        hand.made_hand,
+        hand.cards.join(" ")
+    );
+}